@@ -0,0 +1,213 @@
+//! Reference viewer for the `remote` feature.
+//!
+//! Run the UI on the device with
+//! `cargo run --example remote_viewer --features remote -- device 0.0.0.0:7878`
+//! and connect to it with
+//! `cargo run --example remote_viewer --features remote -- viewer <device>:7878`.
+//! Without arguments both sides run in this process and talk over localhost, without a window.
+//!
+//! The viewer draws the received frames with the OpenGL painter of `egui_glow`. Any other egui
+//! renderer works the same way.
+
+use std::ffi::CString;
+use std::net::{TcpListener, TcpStream};
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use egui_glow::glow;
+use egui_winit_platform::remote::{RemoteConnection, RemoteFrame};
+use egui_winit_platform::{Platform, PlatformDescriptor};
+use glutin::config::ConfigTemplateBuilder;
+use glutin::context::{ContextAttributesBuilder, NotCurrentGlContext};
+use glutin::display::{GetGlDisplay, GlDisplay};
+use glutin::surface::GlSurface;
+use glutin_winit::{DisplayBuilder, GlWindow};
+use raw_window_handle::HasRawWindowHandle;
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::window::WindowBuilder;
+
+type Error = Box<dyn std::error::Error>;
+
+fn main() -> Result<(), Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["device", address] => run_device(TcpListener::bind(address)?),
+        ["viewer", address] => run_viewer(TcpStream::connect(address)?),
+        [] => run_loopback(),
+        _ => Err("usage: remote_viewer [device <address> | viewer <address>]".into()),
+    }
+}
+
+/// Serves the demo UI to one viewer after another.
+fn run_device(listener: TcpListener) -> Result<(), Error> {
+    let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+    let mut clicks = 0;
+
+    for stream in listener.incoming() {
+        let mut connection = RemoteConnection::new(stream?);
+        // The new viewer has none of the textures sent to the previous one.
+        platform.reset_remote_textures();
+        while let Some(input) = connection.receive_input()? {
+            platform.begin_remote_frame(input);
+            egui::CentralPanel::default().show(&platform.context(), |ui| {
+                ui.heading("Remote egui");
+                if ui.button("Click me").clicked() {
+                    clicks += 1;
+                }
+                ui.label(format!("Clicked {} times", clicks));
+            });
+            connection.send_frame(&platform.end_remote_frame())?;
+        }
+    }
+    Ok(())
+}
+
+/// Forwards the input of a window to the device and draws the received frames.
+fn run_viewer(stream: TcpStream) -> Result<(), Error> {
+    let event_loop = EventLoop::new()?;
+    let window_builder = WindowBuilder::new().with_title("Remote egui");
+    let (window, gl_config) = DisplayBuilder::new()
+        .with_window_builder(Some(window_builder))
+        .build(&event_loop, ConfigTemplateBuilder::new(), |mut configs| {
+            configs.next().expect("no OpenGL config available")
+        })?;
+    let window = window.ok_or("Failed to create the window")?;
+
+    let display = gl_config.display();
+    let context_attributes =
+        ContextAttributesBuilder::new().build(Some(window.raw_window_handle()));
+    let surface_attributes = window.build_surface_attributes(Default::default());
+    let (surface, context) = unsafe {
+        let context = display.create_context(&gl_config, &context_attributes)?;
+        let surface = display.create_window_surface(&gl_config, &surface_attributes)?;
+        let context = context.make_current(&surface)?;
+        (surface, context)
+    };
+    let gl = unsafe {
+        glow::Context::from_loader_function(|symbol| {
+            let symbol = CString::new(symbol).expect("invalid OpenGL symbol name");
+            display.get_proc_address(&symbol)
+        })
+    };
+    let mut painter = egui_glow::Painter::new(Rc::new(gl), "", None)?;
+
+    let mut platform = Platform::from_window(&window, PlatformDescriptor::default());
+    let mut connection = RemoteConnection::new(stream);
+
+    event_loop.run(move |event, target| {
+        if platform.handle_event(&event).repaint {
+            window.request_redraw();
+        }
+
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => target.exit(),
+            Event::WindowEvent {
+                event: WindowEvent::Resized(size),
+                ..
+            } => {
+                if let (Some(width), Some(height)) =
+                    (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+                {
+                    surface.resize(&context, width, height);
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::RedrawRequested,
+                ..
+            } => {
+                let frame = connection
+                    .send_input(&platform.take_remote_input())
+                    .and_then(|_| connection.receive_frame());
+                match frame {
+                    Ok(Some(frame)) => {
+                        platform.apply_remote_output(Some(&window), &frame.platform_output);
+
+                        let size = window.inner_size();
+                        let size = [size.width, size.height];
+                        painter.clear(size, [0.0, 0.0, 0.0, 1.0]);
+                        painter.paint_and_update_textures(
+                            size,
+                            frame.pixels_per_point,
+                            &frame.clipped_primitives(),
+                            &frame.textures_delta,
+                        );
+                        if let Err(err) = surface.swap_buffers(&context) {
+                            eprintln!("Failed to present the frame: {}", err);
+                        }
+
+                        if frame.repaint_delay.is_zero() {
+                            window.request_redraw();
+                        }
+                    }
+                    Ok(None) => target.exit(),
+                    Err(err) => {
+                        eprintln!("Connection to the device failed: {}", err);
+                        target.exit();
+                    }
+                }
+            }
+            Event::LoopExiting => painter.destroy(),
+            _ => {}
+        }
+        target.set_control_flow(ControlFlow::Wait);
+    })?;
+    Ok(())
+}
+
+/// Runs the device in a thread and clicks its button from a viewer without a window.
+fn run_loopback() -> Result<(), Error> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    std::thread::spawn(move || {
+        if let Err(err) = run_device(listener) {
+            eprintln!("The device failed: {}", err);
+        }
+    });
+
+    let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+    let mut connection = RemoteConnection::new(TcpStream::connect(address)?);
+
+    let button = egui::pos2(60.0, 45.0);
+    let click = |pressed| egui::Event::PointerButton {
+        pos: button,
+        button: egui::PointerButton::Primary,
+        pressed,
+        modifiers: Default::default(),
+    };
+    let frames = [
+        vec![],
+        vec![egui::Event::PointerMoved(button)],
+        vec![click(true)],
+        vec![click(false)],
+        vec![],
+    ];
+
+    for events in frames {
+        platform.raw_input_mut().events.extend(events);
+        connection.send_input(&platform.take_remote_input())?;
+        let frame = connection
+            .receive_frame()?
+            .ok_or("The device disconnected")?;
+        report(&frame);
+    }
+    Ok(())
+}
+
+fn report(frame: &RemoteFrame) {
+    let vertices: usize = frame
+        .meshes
+        .iter()
+        .map(|remote_mesh| remote_mesh.mesh.vertices.len())
+        .sum();
+    println!(
+        "Received {} meshes with {} vertices and {} texture updates",
+        frame.meshes.len(),
+        vertices,
+        frame.textures_delta.set.len()
+    );
+}
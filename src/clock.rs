@@ -0,0 +1,72 @@
+//! Time sources for the egui animations.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::Instant;
+
+/// A time source that is sampled at the start of every frame.
+pub trait Clock {
+    /// Returns the seconds since some fixed point in time, for example the application start.
+    fn now(&self) -> f64;
+}
+
+/// A [`Clock`] measuring the real time since its creation. This is the default clock.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    /// Creates a new `SystemClock` starting at zero.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+}
+
+/// A [`Clock`] that only advances when told to, for deterministic tests and replays.
+///
+/// Clones share the same time, so keep a clone to control the clock after passing it
+/// to the `Platform`.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    seconds: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Creates a new `ManualClock` starting at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the current time in seconds.
+    pub fn set(&self, seconds: f64) {
+        self.seconds.store(seconds.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Advances the current time by the given seconds.
+    pub fn advance(&self, seconds: f64) {
+        self.set(self.now() + seconds);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f64 {
+        f64::from_bits(self.seconds.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,65 @@
+//! Errors reported by the `Platform`.
+
+use std::fmt;
+
+/// Errors that happen while the `Platform` processes input and output.
+///
+/// None of these errors are fatal, so they are reported instead of returned. See
+/// [`Platform::set_error_callback`](crate::Platform::set_error_callback) and
+/// [`Platform::drain_errors`](crate::Platform::drain_errors).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PlatformError {
+    /// Reading from or writing to the clipboard failed.
+    Clipboard(String),
+    /// Opening a clicked link in the browser failed.
+    OpenUrl(String),
+    /// Winit sent more touch end than touch start events.
+    UnbalancedTouch,
+    /// Loading or saving the egui memory failed.
+    Persistence(String),
+    /// A request to the window failed, e.g. because the platform doesn't support it.
+    Window(String),
+}
+
+impl fmt::Display for PlatformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlatformError::Clipboard(err) => write!(f, "Clipboard error: {}", err),
+            PlatformError::OpenUrl(err) => write!(f, "Failed to open url: {}", err),
+            PlatformError::UnbalancedTouch => write!(
+                f,
+                "Pointer emulation error: Unbalanced touch start/stop events from Winit"
+            ),
+            PlatformError::Persistence(err) => write!(f, "Persistence error: {}", err),
+            PlatformError::Window(err) => write!(f, "Window error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PlatformError {}
+
+macro_rules! log_warning {
+    ($target:literal, $error:expr) => {{
+        #[cfg(feature = "log")]
+        log::warn!(target: $target, "{}", $error);
+        #[cfg(feature = "tracing")]
+        tracing::warn!(target: $target, "{}", $error);
+    }};
+}
+
+/// Writes the error to the enabled logging backend. If none is enabled it is written to stderr,
+/// unless an error callback already handled it.
+pub(crate) fn log_error(error: &PlatformError, handled: bool) {
+    match error {
+        PlatformError::Clipboard(_) => log_warning!("egui_winit_platform::clipboard", error),
+        PlatformError::OpenUrl(_) => log_warning!("egui_winit_platform::links", error),
+        PlatformError::UnbalancedTouch => log_warning!("egui_winit_platform::touch", error),
+        PlatformError::Persistence(_) => log_warning!("egui_winit_platform::persistence", error),
+        PlatformError::Window(_) => log_warning!("egui_winit_platform::window", error),
+    }
+
+    if !handled && cfg!(not(any(feature = "log", feature = "tracing"))) {
+        eprintln!("{}", error);
+    }
+}
@@ -0,0 +1,368 @@
+//! Navigation of the egui UI with a gamepad or controller.
+
+use std::collections::HashMap;
+
+/// A button of a gamepad. The face buttons are named after their position, so `South` is
+/// the A button of an Xbox controller and the cross button of a PlayStation controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// The bottom face button.
+    South,
+    /// The right face button.
+    East,
+    /// The top face button.
+    North,
+    /// The left face button.
+    West,
+    /// Up on the D-pad.
+    DPadUp,
+    /// Down on the D-pad.
+    DPadDown,
+    /// Left on the D-pad.
+    DPadLeft,
+    /// Right on the D-pad.
+    DPadRight,
+    /// The left shoulder button.
+    LeftShoulder,
+    /// The right shoulder button.
+    RightShoulder,
+    /// The start or menu button.
+    Start,
+    /// The select or view button.
+    Select,
+}
+
+/// An axis of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    /// Horizontal axis of the left stick. Positive values point right.
+    LeftStickX,
+    /// Vertical axis of the left stick. Positive values point up.
+    LeftStickY,
+}
+
+/// Input of a gamepad, as delivered by a [`GamepadSource`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    /// A button was pressed.
+    ButtonPressed(GamepadButton),
+    /// A button was released.
+    ButtonReleased(GamepadButton),
+    /// An axis moved to the given value in the range `-1.0..=1.0`.
+    AxisChanged(GamepadAxis, f32),
+}
+
+/// Provides the gamepad input, for example by wrapping a `gilrs::Gilrs` instance, or
+/// synthetic input in tests.
+pub trait GamepadSource {
+    /// Returns the next pending event, or `None` if there is none.
+    fn next_event(&mut self) -> Option<GamepadEvent>;
+}
+
+impl<F: FnMut() -> Option<GamepadEvent>> GamepadSource for F {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        self()
+    }
+}
+
+/// What a gamepad button does in the egui UI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadAction {
+    /// Presses the key with the given modifiers while the button is held.
+    Key(egui::Key, egui::Modifiers),
+    /// Scrolls by the given amount in points when the button is pressed.
+    Scroll(egui::Vec2),
+}
+
+/// Maps gamepad buttons to [`GamepadAction`]s.
+///
+/// The default bindings move the focus with the D-pad and the left stick, press Enter and
+/// Escape with the South and East buttons, move the focus to the next and previous widget with
+/// the North and West buttons and scroll with the shoulder buttons.
+#[derive(Debug, Clone)]
+pub struct GamepadBindings {
+    actions: HashMap<GamepadButton, GamepadAction>,
+    stick_threshold: f32,
+}
+
+impl GamepadBindings {
+    /// Creates new `GamepadBindings` without any bindings.
+    pub fn new() -> Self {
+        Self {
+            actions: HashMap::new(),
+            stick_threshold: 0.5,
+        }
+    }
+
+    /// Binds the button to the action, replacing the previous binding.
+    pub fn bind(mut self, button: GamepadButton, action: GamepadAction) -> Self {
+        self.actions.insert(button, action);
+        self
+    }
+
+    /// Removes the binding of the button.
+    pub fn unbind(mut self, button: GamepadButton) -> Self {
+        self.actions.remove(&button);
+        self
+    }
+
+    /// Sets how far the left stick has to be moved to act like the D-pad. Defaults to 0.5.
+    pub fn with_stick_threshold(mut self, stick_threshold: f32) -> Self {
+        self.stick_threshold = stick_threshold;
+        self
+    }
+}
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        let key = |key| GamepadAction::Key(key, egui::Modifiers::NONE);
+        let scroll = |y| GamepadAction::Scroll(egui::vec2(0.0, y));
+        Self::new()
+            .bind(GamepadButton::DPadUp, key(egui::Key::ArrowUp))
+            .bind(GamepadButton::DPadDown, key(egui::Key::ArrowDown))
+            .bind(GamepadButton::DPadLeft, key(egui::Key::ArrowLeft))
+            .bind(GamepadButton::DPadRight, key(egui::Key::ArrowRight))
+            .bind(GamepadButton::South, key(egui::Key::Enter))
+            .bind(GamepadButton::East, key(egui::Key::Escape))
+            .bind(GamepadButton::North, key(egui::Key::Tab))
+            .bind(
+                GamepadButton::West,
+                GamepadAction::Key(egui::Key::Tab, egui::Modifiers::SHIFT),
+            )
+            .bind(GamepadButton::LeftShoulder, scroll(100.0))
+            .bind(GamepadButton::RightShoulder, scroll(-100.0))
+    }
+}
+
+/// Translates the input of a [`GamepadSource`] into egui events.
+///
+/// Set it with [`Platform::set_gamepad_navigation`](crate::Platform::set_gamepad_navigation).
+/// The source is polled in every `begin_frame`, so keep the event loop running while a gamepad
+/// is in use.
+pub struct GamepadNavigation {
+    source: Box<dyn GamepadSource>,
+    bindings: GamepadBindings,
+    // The D-pad button emulated by each stick axis.
+    stick_buttons: HashMap<GamepadAxis, GamepadButton>,
+}
+
+impl GamepadNavigation {
+    /// Creates a new `GamepadNavigation` reading from the given source with the default bindings.
+    pub fn new(source: impl GamepadSource + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+            bindings: GamepadBindings::default(),
+            stick_buttons: HashMap::new(),
+        }
+    }
+
+    /// Sets the bindings.
+    pub fn set_bindings(&mut self, bindings: GamepadBindings) {
+        self.bindings = bindings;
+    }
+
+    /// Returns the bindings.
+    pub fn bindings(&self) -> &GamepadBindings {
+        &self.bindings
+    }
+
+    /// Reads all pending input of the source and appends the resulting egui events.
+    pub(crate) fn poll(&mut self, events: &mut Vec<egui::Event>) {
+        while let Some(event) = self.source.next_event() {
+            match event {
+                GamepadEvent::ButtonPressed(button) => self.button(button, true, events),
+                GamepadEvent::ButtonReleased(button) => self.button(button, false, events),
+                GamepadEvent::AxisChanged(axis, value) => self.axis(axis, value, events),
+            }
+        }
+    }
+
+    fn axis(&mut self, axis: GamepadAxis, value: f32, events: &mut Vec<egui::Event>) {
+        let threshold = self.bindings.stick_threshold;
+        let button = match axis {
+            GamepadAxis::LeftStickX if value >= threshold => Some(GamepadButton::DPadRight),
+            GamepadAxis::LeftStickX if value <= -threshold => Some(GamepadButton::DPadLeft),
+            GamepadAxis::LeftStickY if value >= threshold => Some(GamepadButton::DPadUp),
+            GamepadAxis::LeftStickY if value <= -threshold => Some(GamepadButton::DPadDown),
+            _ => None,
+        };
+
+        let previous = self.stick_buttons.get(&axis).copied();
+        if previous == button {
+            return;
+        }
+        if let Some(previous) = previous {
+            self.button(previous, false, events);
+        }
+        match button {
+            Some(button) => {
+                self.stick_buttons.insert(axis, button);
+                self.button(button, true, events);
+            }
+            None => {
+                self.stick_buttons.remove(&axis);
+            }
+        }
+    }
+
+    fn button(&self, button: GamepadButton, pressed: bool, events: &mut Vec<egui::Event>) {
+        match self.bindings.actions.get(&button) {
+            Some(GamepadAction::Key(key, modifiers)) => events.push(egui::Event::Key {
+                key: *key,
+                pressed,
+                repeat: false,
+                modifiers: *modifiers,
+            }),
+            Some(GamepadAction::Scroll(delta)) if pressed => {
+                events.push(egui::Event::Scroll(*delta))
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    /// Polls the given input through a closure `GamepadSource` and returns the egui events.
+    fn poll(navigation: &mut GamepadNavigation, input: &[GamepadEvent]) -> Vec<egui::Event> {
+        let mut events = Vec::new();
+        let mut input: VecDeque<GamepadEvent> = input.iter().copied().collect();
+        navigation.source = Box::new(move || input.pop_front());
+        navigation.poll(&mut events);
+        events
+    }
+
+    fn key(key: egui::Key, pressed: bool) -> egui::Event {
+        egui::Event::Key {
+            key,
+            pressed,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        }
+    }
+
+    fn navigation() -> GamepadNavigation {
+        GamepadNavigation::new(|| None)
+    }
+
+    #[test]
+    fn dpad_presses_and_releases_arrow_keys() {
+        let events = poll(
+            &mut navigation(),
+            &[
+                GamepadEvent::ButtonPressed(GamepadButton::DPadDown),
+                GamepadEvent::ButtonReleased(GamepadButton::DPadDown),
+                GamepadEvent::ButtonPressed(GamepadButton::DPadLeft),
+            ],
+        );
+        assert_eq!(
+            events,
+            [
+                key(egui::Key::ArrowDown, true),
+                key(egui::Key::ArrowDown, false),
+                key(egui::Key::ArrowLeft, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn stick_acts_like_the_dpad_beyond_the_threshold() {
+        let mut navigation = navigation();
+        let events = poll(
+            &mut navigation,
+            &[
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, 0.3),
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, 0.6),
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, 0.9),
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, -0.7),
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, 0.1),
+            ],
+        );
+        assert_eq!(
+            events,
+            [
+                key(egui::Key::ArrowRight, true),
+                key(egui::Key::ArrowRight, false),
+                key(egui::Key::ArrowLeft, true),
+                key(egui::Key::ArrowLeft, false),
+            ]
+        );
+
+        let events = poll(
+            &mut navigation,
+            &[GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, 0.8)],
+        );
+        assert_eq!(events, [key(egui::Key::ArrowUp, true)]);
+    }
+
+    #[test]
+    fn face_buttons_press_enter_and_escape() {
+        let events = poll(
+            &mut navigation(),
+            &[
+                GamepadEvent::ButtonPressed(GamepadButton::South),
+                GamepadEvent::ButtonReleased(GamepadButton::South),
+                GamepadEvent::ButtonPressed(GamepadButton::East),
+            ],
+        );
+        assert_eq!(
+            events,
+            [
+                key(egui::Key::Enter, true),
+                key(egui::Key::Enter, false),
+                key(egui::Key::Escape, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn shoulder_buttons_scroll_when_pressed() {
+        let events = poll(
+            &mut navigation(),
+            &[
+                GamepadEvent::ButtonPressed(GamepadButton::LeftShoulder),
+                GamepadEvent::ButtonReleased(GamepadButton::LeftShoulder),
+                GamepadEvent::ButtonPressed(GamepadButton::RightShoulder),
+            ],
+        );
+        assert_eq!(
+            events,
+            [
+                egui::Event::Scroll(egui::vec2(0.0, 100.0)),
+                egui::Event::Scroll(egui::vec2(0.0, -100.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_bindings_replace_and_remove_actions() {
+        let mut navigation = navigation();
+        navigation.set_bindings(
+            GamepadBindings::default()
+                .bind(
+                    GamepadButton::South,
+                    GamepadAction::Key(egui::Key::Space, egui::Modifiers::NONE),
+                )
+                .unbind(GamepadButton::East)
+                .with_stick_threshold(0.9),
+        );
+
+        let events = poll(
+            &mut navigation,
+            &[
+                GamepadEvent::ButtonPressed(GamepadButton::South),
+                GamepadEvent::ButtonPressed(GamepadButton::East),
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, -0.8),
+                GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, -0.95),
+            ],
+        );
+        assert_eq!(
+            events,
+            [key(egui::Key::Space, true), key(egui::Key::ArrowDown, true)]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Injection of egui events from other threads.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+pub(crate) type EventQueue = Arc<Mutex<Vec<egui::Event>>>;
+type WakeUp = Arc<Mutex<dyn FnMut() + Send>>;
+
+/// A handle to queue egui events from any thread, for example for automation or remote control.
+///
+/// Create it with [`Platform::injector`](crate::Platform::injector). The queued events are passed
+/// to egui in the next [`Platform::begin_frame`](crate::Platform::begin_frame), after the input
+/// filters were applied to them.
+#[derive(Clone)]
+pub struct InputInjector {
+    queue: EventQueue,
+    wake_up: Option<WakeUp>,
+}
+
+impl InputInjector {
+    pub(crate) fn new(queue: EventQueue) -> Self {
+        Self {
+            queue,
+            wake_up: None,
+        }
+    }
+
+    /// Sends the given user event through the proxy after every injection, so that a waiting
+    /// event loop wakes up. [`Platform::handle_event`](crate::Platform::handle_event) requests a
+    /// repaint for user events while injected events are queued.
+    pub fn with_event_loop_proxy<T: Clone + Send + 'static>(
+        mut self,
+        proxy: winit::event_loop::EventLoopProxy<T>,
+        wake_up_event: T,
+    ) -> Self {
+        self.wake_up = Some(Arc::new(Mutex::new(move || {
+            // The event loop is gone, so there is nobody left to wake up.
+            let _ = proxy.send_event(wake_up_event.clone());
+        })));
+        self
+    }
+
+    /// Queues an egui event.
+    pub fn inject(&self, event: egui::Event) {
+        self.inject_all(std::iter::once(event));
+    }
+
+    /// Queues several egui events at once, so that they are passed to egui in the same frame.
+    pub fn inject_all(&self, events: impl IntoIterator<Item = egui::Event>) {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(events);
+
+        if let Some(wake_up) = &self.wake_up {
+            (wake_up.lock().unwrap_or_else(PoisonError::into_inner))();
+        }
+    }
+}
+
+/// Takes all queued events.
+pub(crate) fn take_events(queue: &EventQueue) -> Vec<egui::Event> {
+    std::mem::take(&mut *queue.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Returns `true` if events are queued.
+pub(crate) fn has_events(queue: &EventQueue) -> bool {
+    !queue
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_empty()
+}
@@ -0,0 +1,150 @@
+//! Filters that inspect and rewrite the egui events before egui sees them.
+
+use std::collections::HashMap;
+
+/// Inspects, rewrites, drops or adds the egui events produced by
+/// [`Platform::handle_event`](crate::Platform::handle_event).
+///
+/// Filters are applied in the order they were added to the `Platform`. The output of one
+/// filter is the input of the next one.
+pub trait InputFilter {
+    /// Called for every produced event. Push the events egui should receive into `output`:
+    /// nothing to drop the event, the (modified) event to keep it and further events to
+    /// inject them.
+    fn filter(&mut self, event: egui::Event, output: &mut Vec<egui::Event>);
+}
+
+impl<F: FnMut(egui::Event, &mut Vec<egui::Event>)> InputFilter for F {
+    fn filter(&mut self, event: egui::Event, output: &mut Vec<egui::Event>) {
+        self(event, output)
+    }
+}
+
+/// Replaces keys of key events with other keys.
+#[derive(Debug, Clone, Default)]
+pub struct KeyRemap {
+    table: HashMap<egui::Key, egui::Key>,
+}
+
+impl KeyRemap {
+    /// Creates a new `KeyRemap` without any mappings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the key `from` to the key `to`.
+    pub fn map(mut self, from: egui::Key, to: egui::Key) -> Self {
+        self.table.insert(from, to);
+        self
+    }
+}
+
+impl InputFilter for KeyRemap {
+    fn filter(&mut self, mut event: egui::Event, output: &mut Vec<egui::Event>) {
+        if let egui::Event::Key { key, .. } = &mut event {
+            if let Some(mapped) = self.table.get(key) {
+                *key = *mapped;
+            }
+        }
+        output.push(event);
+    }
+}
+
+/// Drops keyboard shortcuts, for example to lock down a kiosk application.
+#[derive(Debug, Clone, Default)]
+pub struct ShortcutBlocker {
+    blocked: Vec<egui::KeyboardShortcut>,
+}
+
+impl ShortcutBlocker {
+    /// Creates a new `ShortcutBlocker` without any blocked shortcuts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Blocks the given shortcut.
+    pub fn block(mut self, shortcut: egui::KeyboardShortcut) -> Self {
+        self.blocked.push(shortcut);
+        self
+    }
+
+    fn blocks_command(&self, key: egui::Key) -> bool {
+        self.blocked.iter().any(|shortcut| {
+            shortcut.key == key && (shortcut.modifiers.command || shortcut.modifiers.ctrl)
+        })
+    }
+}
+
+impl InputFilter for ShortcutBlocker {
+    fn filter(&mut self, event: egui::Event, output: &mut Vec<egui::Event>) {
+        let blocked = match &event {
+            egui::Event::Key { key, modifiers, .. } => self
+                .blocked
+                .iter()
+                .any(|shortcut| shortcut.key == *key && modifiers.matches(shortcut.modifiers)),
+            // Copy, cut and paste shortcuts are translated into their own events.
+            egui::Event::Copy => self.blocks_command(egui::Key::C),
+            egui::Event::Cut => self.blocks_command(egui::Key::X),
+            egui::Event::Paste(_) => self.blocks_command(egui::Key::V),
+            _ => false,
+        };
+
+        if !blocked {
+            output.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(filter: &mut impl InputFilter, event: egui::Event) -> Vec<egui::Event> {
+        let mut output = Vec::new();
+        filter.filter(event, &mut output);
+        output
+    }
+
+    #[test]
+    fn shortcut_blocker_blocks_clipboard_events() {
+        for modifiers in [egui::Modifiers::CTRL, egui::Modifiers::COMMAND] {
+            let mut blocker = ShortcutBlocker::new()
+                .block(egui::KeyboardShortcut::new(modifiers, egui::Key::C))
+                .block(egui::KeyboardShortcut::new(modifiers, egui::Key::X))
+                .block(egui::KeyboardShortcut::new(modifiers, egui::Key::V));
+
+            assert!(filter(&mut blocker, egui::Event::Copy).is_empty());
+            assert!(filter(&mut blocker, egui::Event::Cut).is_empty());
+            assert!(filter(&mut blocker, egui::Event::Paste("text".to_string())).is_empty());
+        }
+
+        let mut blocker = ShortcutBlocker::new().block(egui::KeyboardShortcut::new(
+            egui::Modifiers::CTRL,
+            egui::Key::C,
+        ));
+        assert_eq!(
+            filter(&mut blocker, egui::Event::Cut),
+            vec![egui::Event::Cut]
+        );
+    }
+
+    #[test]
+    fn shortcut_blocker_blocks_key_events() {
+        let key = |modifiers| egui::Event::Key {
+            key: egui::Key::W,
+            pressed: true,
+            repeat: false,
+            modifiers,
+        };
+        let mut blocker = ShortcutBlocker::new().block(egui::KeyboardShortcut::new(
+            egui::Modifiers::CTRL,
+            egui::Key::W,
+        ));
+
+        assert!(filter(&mut blocker, key(egui::Modifiers::CTRL)).is_empty());
+        assert_eq!(
+            filter(&mut blocker, key(egui::Modifiers::NONE)),
+            vec![key(egui::Modifiers::NONE)]
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Several egui contexts layered on top of each other in the same window.
+
+use winit::event::{ElementState, Event, Touch, TouchPhase, WindowEvent};
+
+use crate::{EventResponse, Platform};
+
+/// Whom a window event is routed to.
+enum Routing {
+    /// Every layer receives the event.
+    All,
+    /// The event is passed down until a layer wants pointer input.
+    Pointer,
+    /// The event is only passed to the topmost layer that wants keyboard input.
+    Keyboard,
+}
+
+/// Stacks several [`Platform`]s in the same window, for example a HUD and a debug UI.
+///
+/// Pointer events are passed from the top layer down until a layer wants pointer input, or only
+/// to the layer that is using the pointer, e.g. for a drag. Releases also reach every layer that
+/// received the press. Keyboard events only reach the topmost layer that wants keyboard input.
+/// Layers are ordered from bottom to top.
+#[derive(Default)]
+pub struct LayeredPlatform {
+    layers: Vec<Platform>,
+    // The layers that received a press which wasn't released yet.
+    pressed_layers: Vec<usize>,
+    // Whether the IME of the window was last allowed, if it was applied yet.
+    applied_text_input: Option<bool>,
+}
+
+impl LayeredPlatform {
+    /// Creates a new `LayeredPlatform` without any layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer on top of all other layers and returns its index.
+    pub fn push_layer(&mut self, platform: Platform) -> usize {
+        self.layers.push(platform);
+        self.layers.len() - 1
+    }
+
+    /// Returns the layer with the given index. Index `0` is the bottom layer.
+    pub fn layer(&self, index: usize) -> Option<&Platform> {
+        self.layers.get(index)
+    }
+
+    /// Returns the layer with the given index mutably. Index `0` is the bottom layer.
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut Platform> {
+        self.layers.get_mut(index)
+    }
+
+    /// Returns the number of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if there are no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Dispatches the given winit event to the layers. Returns the combined response of all
+    /// layers that received the event.
+    pub fn handle_event<T: 'static>(&mut self, winit_event: &Event<T>) -> EventResponse {
+        let routing = match winit_event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::Touch(_) => Routing::Pointer,
+                WindowEvent::KeyboardInput { .. } | WindowEvent::Ime(_) => Routing::Keyboard,
+                _ => Routing::All,
+            },
+            _ => Routing::All,
+        };
+
+        let mut response = EventResponse::default();
+        let mut dispatch = |layer: &mut Platform| {
+            let layer_response = layer.handle_event(winit_event);
+            response.consumed |= layer_response.consumed;
+            response.repaint |= layer_response.repaint;
+        };
+
+        match routing {
+            Routing::All => {
+                for layer in &mut self.layers {
+                    dispatch(layer);
+                }
+            }
+            Routing::Pointer => {
+                let (pressed, released) = match winit_event {
+                    Event::WindowEvent {
+                        event: WindowEvent::MouseInput { state, .. },
+                        ..
+                    } => (
+                        *state == ElementState::Pressed,
+                        *state == ElementState::Released,
+                    ),
+                    Event::WindowEvent {
+                        event: WindowEvent::Touch(Touch { phase, .. }),
+                        ..
+                    } => (
+                        *phase == TouchPhase::Started,
+                        matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled),
+                    ),
+                    _ => (false, false),
+                };
+                // A layer that is dragging keeps the pointer, even if it moves over a higher layer.
+                let owner = self
+                    .layers
+                    .iter()
+                    .rposition(|layer| layer.context().is_using_pointer());
+
+                let mut captured = false;
+                for (index, layer) in self.layers.iter_mut().enumerate().rev() {
+                    let receives = match owner {
+                        Some(owner) => index == owner,
+                        None => !captured,
+                    } || (released && self.pressed_layers.contains(&index));
+                    if !receives {
+                        // The pointer is hidden behind a higher layer.
+                        layer.raw_input_mut().events.push(egui::Event::PointerGone);
+                        continue;
+                    }
+                    dispatch(layer);
+                    captured |= layer.context().wants_pointer_input();
+                    if pressed && !self.pressed_layers.contains(&index) {
+                        self.pressed_layers.push(index);
+                    }
+                }
+                if released {
+                    self.pressed_layers.clear();
+                }
+            }
+            Routing::Keyboard => {
+                match self
+                    .layers
+                    .iter_mut()
+                    .rev()
+                    .find(|layer| layer.context().wants_keyboard_input())
+                {
+                    Some(layer) => dispatch(layer),
+                    // Nobody has keyboard focus, so every layer may react to shortcuts.
+                    None => {
+                        for layer in &mut self.layers {
+                            dispatch(layer);
+                        }
+                    }
+                }
+            }
+        }
+
+        response
+    }
+
+    /// Returns `true` if any layer should handle the event exclusively.
+    pub fn captures_event<T>(&self, winit_event: &Event<T>) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.captures_event(winit_event))
+    }
+
+    /// Starts a new frame for all layers.
+    pub fn begin_frame(&mut self) {
+        for layer in &mut self.layers {
+            layer.begin_frame();
+        }
+    }
+
+    /// Ends the frame of all layers and returns their outputs ordered from bottom to top.
+    /// If the optional `window` is set, the cursor icon is taken from the topmost layer
+    /// that wants pointer input, and the IME of the window is allowed while any layer wants
+    /// text input. Layers with a text input callback still receive their own state.
+    pub fn end_frame(&mut self, window: Option<&winit::window::Window>) -> Vec<egui::FullOutput> {
+        let outputs: Vec<egui::FullOutput> = self
+            .layers
+            .iter_mut()
+            .map(|layer| layer.end_frame(None))
+            .collect();
+
+        if let Some(window) = window {
+            let cursor_layer = self
+                .layers
+                .iter()
+                .rposition(|layer| layer.context().wants_pointer_input())
+                .unwrap_or(0);
+            if let (Some(layer), Some(output)) =
+                (self.layers.get(cursor_layer), outputs.get(cursor_layer))
+            {
+                layer.update_cursor(window, output.platform_output.cursor_icon);
+            }
+
+            let text_input = self.layers.iter().any(Platform::wants_text_input);
+            if self.applied_text_input != Some(text_input) {
+                window.set_ime_allowed(text_input);
+                self.applied_text_input = Some(text_input);
+            }
+        }
+
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2};
+    use winit::dpi::PhysicalPosition;
+
+    use super::*;
+    use crate::PlatformDescriptor;
+
+    fn window_event(event: WindowEvent) -> Event<()> {
+        Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event,
+        }
+    }
+
+    fn cursor_moved(x: f64, y: f64) -> Event<()> {
+        window_event(WindowEvent::CursorMoved {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            position: PhysicalPosition::new(x, y),
+        })
+    }
+
+    fn mouse_input(state: ElementState) -> Event<()> {
+        window_event(WindowEvent::MouseInput {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            state,
+            button: winit::event::MouseButton::Left,
+        })
+    }
+
+    #[test]
+    fn drag_of_a_lower_layer_ends_over_a_higher_layer() {
+        let mut layered = LayeredPlatform::new();
+        layered.push_layer(Platform::new(PlatformDescriptor::new(800, 600, 1.0)));
+
+        let run_frame = |layered: &mut LayeredPlatform| {
+            layered.begin_frame();
+            let context = layered.layer(0).unwrap().context();
+            egui::CentralPanel::default().show(&context, |ui| {
+                ui.interact(ui.max_rect(), egui::Id::new("scene"), egui::Sense::drag());
+            });
+            if let Some(top) = layered.layer(1) {
+                egui::Area::new("toolbar")
+                    .fixed_pos(pos2(300.0, 200.0))
+                    .show(&top.context(), |ui| ui.allocate_space(vec2(200.0, 200.0)));
+            }
+            layered.end_frame(None);
+        };
+        let is_using_pointer = |layered: &LayeredPlatform, index: usize| {
+            layered.layer(index).unwrap().context().is_using_pointer()
+        };
+
+        run_frame(&mut layered);
+        layered.handle_event(&cursor_moved(50.0, 50.0));
+        run_frame(&mut layered);
+        layered.handle_event(&mouse_input(ElementState::Pressed));
+        run_frame(&mut layered);
+        layered.handle_event(&cursor_moved(100.0, 100.0));
+        run_frame(&mut layered);
+        assert!(is_using_pointer(&layered, 0));
+
+        // A toolbar that didn't see the press appears, and the drag continues over it.
+        layered.push_layer(Platform::new(PlatformDescriptor::new(800, 600, 1.0)));
+        run_frame(&mut layered);
+        for _ in 0..3 {
+            layered.handle_event(&cursor_moved(400.0, 300.0));
+            run_frame(&mut layered);
+        }
+        assert!(is_using_pointer(&layered, 0));
+
+        layered.handle_event(&mouse_input(ElementState::Released));
+        run_frame(&mut layered);
+        run_frame(&mut layered);
+        assert!(!is_using_pointer(&layered, 0));
+    }
+}
@@ -5,7 +5,37 @@
 //! A basic usage example can be found [here](https://github.com/hasenbanck/egui_example).
 #![warn(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+
+mod clock;
+mod error;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod injector;
+mod input_filter;
+mod layered;
+mod on_screen_keyboard;
+#[cfg(feature = "persistence")]
+mod persistence;
+#[cfg(feature = "remote")]
+pub mod remote;
+mod software_cursor;
+
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use error::PlatformError;
+#[cfg(feature = "gamepad")]
+pub use gamepad::{
+    GamepadAction, GamepadAxis, GamepadBindings, GamepadButton, GamepadEvent, GamepadNavigation,
+    GamepadSource,
+};
+pub use injector::InputInjector;
+pub use input_filter::{InputFilter, KeyRemap, ShortcutBlocker};
+pub use layered::LayeredPlatform;
+pub use on_screen_keyboard::{KeyboardLayout, OnScreenKeyboard};
+
+#[cfg(feature = "persistence")]
+pub use persistence::{FileFormat, FileStorage, Storage};
+pub use software_cursor::{CursorId, CursorImage, SoftwareCursor};
 
 #[cfg(feature = "clipboard")]
 use copypasta::{ClipboardContext, ClipboardProvider};
@@ -14,59 +44,358 @@ use egui::{
     Context, Pos2,
 };
 use winit::{
-    dpi::PhysicalSize,
+    dpi::{PhysicalPosition, PhysicalSize},
     event::{Event, TouchPhase, WindowEvent::*, Ime},
-    window::CursorIcon, keyboard::{ModifiersState, NamedKey, Key},
+    window::{CursorIcon, Theme}, keyboard::{ModifiersState, NamedKey, Key},
 };
 
+use error::log_error;
+
+/// Upper bound for the predicted frame time, so that animations don't jump after idle periods.
+const MAX_PREDICTED_DT: f32 = 0.1;
+
+/// Change of the zoom factor per zoom shortcut.
+const ZOOM_STEP: f32 = 0.1;
+
+/// Number of errors that are kept until they are drained.
+const MAX_QUEUED_ERRORS: usize = 64;
+
+type ErrorCallback = Box<dyn FnMut(&PlatformError)>;
+type TextInputCallback = Box<dyn FnMut(bool)>;
+type UserEventHandler<T> = Box<dyn FnMut(&T, &mut Vec<egui::Event>) -> bool>;
+
 /// Configures the creation of the `Platform`.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct PlatformDescriptor {
     /// Width of the window in physical pixel.
     pub physical_width: u32,
     /// Height of the window in physical pixel.
     pub physical_height: u32,
-    /// HiDPI scale factor.
+    /// HiDPI scale factor. Defaults to 1.
     pub scale_factor: f64,
     /// Egui font configuration.
     pub font_definitions: egui::FontDefinitions,
     /// Egui style configuration.
     pub style: egui::Style,
+    /// Theme used to select the egui visuals. `None` keeps the visuals of `style`.
+    pub theme: Option<ThemePreference>,
+    /// Visuals used for the dark theme. Defaults to `egui::Visuals::dark()`.
+    pub dark_visuals: Option<egui::Visuals>,
+    /// Visuals used for the light theme. Defaults to `egui::Visuals::light()`.
+    pub light_visuals: Option<egui::Visuals>,
+    /// Region of the window in physical pixel egui is drawn into. `None` uses the whole window.
+    pub target_rect: Option<egui::Rect>,
+    /// Configures how mouse wheel input is translated into scrolling.
+    pub scroll_config: ScrollConfig,
+    /// Capabilities of the renderer that draws the egui output.
+    pub renderer_capabilities: RendererCapabilities,
+}
+
+impl Default for PlatformDescriptor {
+    fn default() -> Self {
+        Self {
+            physical_width: 0,
+            physical_height: 0,
+            scale_factor: 1.0,
+            font_definitions: Default::default(),
+            style: Default::default(),
+            theme: None,
+            dark_visuals: None,
+            light_visuals: None,
+            target_rect: None,
+            scroll_config: Default::default(),
+            renderer_capabilities: Default::default(),
+        }
+    }
+}
+
+impl PlatformDescriptor {
+    /// Creates a new `PlatformDescriptor` for a window of the given size and scale factor.
+    /// All other options use their defaults.
+    pub fn new(physical_width: u32, physical_height: u32, scale_factor: f64) -> Self {
+        Self {
+            physical_width,
+            physical_height,
+            scale_factor,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the egui font configuration.
+    pub fn with_font_definitions(mut self, font_definitions: egui::FontDefinitions) -> Self {
+        self.font_definitions = font_definitions;
+        self
+    }
+
+    /// Sets the egui style configuration.
+    pub fn with_style(mut self, style: egui::Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the theme used to select the egui visuals.
+    pub fn with_theme(mut self, theme: ThemePreference) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Sets the visuals used for the dark and the light theme.
+    pub fn with_theme_visuals(mut self, dark: egui::Visuals, light: egui::Visuals) -> Self {
+        self.dark_visuals = Some(dark);
+        self.light_visuals = Some(light);
+        self
+    }
+
+    /// Sets the region of the window in physical pixel egui is drawn into.
+    pub fn with_target_rect(mut self, target_rect: egui::Rect) -> Self {
+        self.target_rect = Some(target_rect);
+        self
+    }
+
+    /// Sets how mouse wheel input is translated into scrolling.
+    pub fn with_scroll_config(mut self, scroll_config: ScrollConfig) -> Self {
+        self.scroll_config = scroll_config;
+        self
+    }
+
+    /// Sets the capabilities of the renderer that draws the egui output.
+    pub fn with_renderer_capabilities(mut self, capabilities: RendererCapabilities) -> Self {
+        self.renderer_capabilities = capabilities;
+        self
+    }
+
+    /// Sets the maximum side length of textures the renderer supports.
+    pub fn with_max_texture_side(mut self, max_texture_side: usize) -> Self {
+        self.renderer_capabilities.max_texture_side = Some(max_texture_side);
+        self
+    }
+}
+
+/// Describes what the renderer drawing the egui output supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RendererCapabilities {
+    /// Maximum side length of textures, e.g. of the font atlas. `None` uses egui's default.
+    ///
+    /// Egui keeps the last limit it was given, so switching back to `None` at runtime leaves
+    /// egui on the previous value. Set the default of the new renderer explicitly instead.
+    pub max_texture_side: Option<usize>,
+    /// Color space of the render target. It is passed on in
+    /// [`PlatformFrame::target_color_space`].
+    pub target_color_space: TargetColorSpace,
+}
+
+/// Color space of the render target.
+///
+/// Egui outputs colors in gamma (sRGB) space. Renderers drawing into a linear target have to
+/// convert them in their shaders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetColorSpace {
+    /// The target applies the sRGB encoding itself, e.g. a `*Srgb` texture format.
+    #[default]
+    Srgb,
+    /// The target stores the color values as they are written.
+    Linear,
+}
+
+/// Configures how mouse wheel input is translated into scrolling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollConfig {
+    /// Points scrolled for every line of a line based mouse wheel. Defaults to 8.
+    pub line_height: f32,
+    /// Factor applied to pixel based scroll input, e.g. of touchpads. Defaults to 1.
+    pub pixel_multiplier: f32,
+}
+
+impl Default for ScrollConfig {
+    fn default() -> Self {
+        Self {
+            line_height: 8.0,
+            pixel_multiplier: 1.0,
+        }
+    }
+}
+
+/// Lifecycle state of the application, relevant on mobile platforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleState {
+    /// The application is in the foreground and may render.
+    Running,
+    /// The application was suspended. Surfaces and textures have to be dropped.
+    Suspended,
+}
+
+/// Maps positions in the window to positions in egui. This allows to use egui on arbitrary
+/// surfaces, e.g. on a texture in a 3D scene by raycasting the window position.
+pub trait PointerTransform {
+    /// Maps a position in physical window pixel to a position in egui points.
+    /// Returns `None` if the position doesn't hit the egui surface.
+    fn transform(&self, position: PhysicalPosition<f64>) -> Option<egui::Pos2>;
+}
+
+impl<F: Fn(PhysicalPosition<f64>) -> Option<egui::Pos2>> PointerTransform for F {
+    fn transform(&self, position: PhysicalPosition<f64>) -> Option<egui::Pos2> {
+        self(position)
+    }
+}
+
+/// Selects whether egui uses dark or light visuals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemePreference {
+    /// Follow the theme of the operating system.
+    System,
+    /// Always use the light visuals.
+    Light,
+    /// Always use the dark visuals.
+    Dark,
+}
+
+/// Describes which parts of the screen changed compared to the previous frame.
+///
+/// Returned by [`Platform::end_frame_with_damage`]. All rectangles are in logical points,
+/// multiply them with the pixels per point to get scissor rectangles in physical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameDamage {
+    /// Nothing changed since the last frame. The previously presented image can be reused.
+    Unchanged,
+    /// Only the given rectangles need to be redrawn.
+    Dirty(Vec<egui::Rect>),
+}
+
+/// The result of [`Platform::handle_event`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventResponse {
+    /// `true` if egui consumed the event, so it should not be forwarded to the application.
+    pub consumed: bool,
+    /// `true` if the event changed the egui input and the UI should be redrawn.
+    pub repaint: bool,
+}
+
+/// A finished and tessellated frame, returned by [`Platform::end_frame_tessellated`].
+pub struct PlatformFrame {
+    /// Tessellated shapes of the frame, ready to be drawn by the renderer.
+    pub clipped_primitives: Vec<egui::ClippedPrimitive>,
+    /// Textures that need to be uploaded before and freed after drawing.
+    pub textures_delta: egui::TexturesDelta,
+    /// Non-rendering related output of egui.
+    pub platform_output: egui::PlatformOutput,
+    /// Size of the window in physical pixel.
+    pub physical_size: PhysicalSize<u32>,
+    /// The pixels per point that were used for tessellation.
+    pub pixels_per_point: f32,
+    /// Color space of the render target, from the current [`RendererCapabilities`]. Convert the
+    /// vertex colors in the shader if it is [`TargetColorSpace::Linear`].
+    pub target_color_space: TargetColorSpace,
+}
+
+/// Configures a window builder for a transparent, undecorated overlay that stays on top of other
+/// windows. Combine it with [`Platform::set_click_through`] and clear the frame with a transparent
+/// color to let clicks on empty regions reach the application below.
+pub fn overlay_window_builder(
+    builder: winit::window::WindowBuilder,
+) -> winit::window::WindowBuilder {
+    builder
+        .with_transparent(true)
+        .with_decorations(false)
+        .with_window_level(winit::window::WindowLevel::AlwaysOnTop)
 }
 
 #[cfg(feature = "webbrowser")]
-fn handle_links(output: &egui::PlatformOutput) {
+fn handle_links(output: &egui::PlatformOutput) -> Result<(), PlatformError> {
     if let Some(open_url) = &output.open_url {
         // This does not handle open_url.new_tab
         // webbrowser does not support web anyway
-        if let Err(err) = webbrowser::open(&open_url.url) {
-            eprintln!("Failed to open url: {}", err);
-        }
+        webbrowser::open(&open_url.url).map_err(|err| PlatformError::OpenUrl(err.to_string()))?;
     }
+    Ok(())
 }
 
 #[cfg(feature = "clipboard")]
-fn handle_clipboard(output: &egui::PlatformOutput, clipboard: Option<&mut ClipboardContext>) {
+fn handle_clipboard(
+    output: &egui::PlatformOutput,
+    clipboard: Option<&mut ClipboardContext>,
+) -> Result<(), PlatformError> {
     if !output.copied_text.is_empty() {
         if let Some(clipboard) = clipboard {
-            if let Err(err) = clipboard.set_contents(output.copied_text.clone()) {
-                eprintln!("Copy/Cut error: {}", err);
-            }
+            clipboard
+                .set_contents(output.copied_text.clone())
+                .map_err(|err| PlatformError::Clipboard(err.to_string()))?;
         }
     }
+    Ok(())
 }
 
 /// Provides the integration between egui and winit.
 pub struct Platform {
     scale_factor: f64,
+    // Zoom factor given to egui that takes effect in the next frame. Otherwise the zoom factor
+    // of the context is used.
+    pending_zoom_factor: Option<f32>,
+    zoom_limits: (f32, f32),
+    zoom_with_keyboard: bool,
+    physical_size: PhysicalSize<u32>,
+    target_rect: Option<egui::Rect>,
+    pointer_transform: Option<Box<dyn PointerTransform>>,
+    scroll_config: ScrollConfig,
+    renderer_capabilities: RendererCapabilities,
+    input_filters: Vec<Box<dyn InputFilter>>,
+    // A `UserEventHandler<T>` for the user event type `T` of the event loop.
+    user_event_handler: Option<Box<dyn std::any::Any>>,
+    injected_events: injector::EventQueue,
+
+    clock: Box<dyn Clock>,
+    last_frame_time: Option<f64>,
+    refresh_rate: Option<f32>,
+
+    errors: VecDeque<PlatformError>,
+    error_callback: Option<ErrorCallback>,
+
+    wants_text_input: bool,
+    // The text input state that was last applied to the window or callback.
+    applied_text_input: Option<bool>,
+    text_input_callback: Option<TextInputCallback>,
+    on_screen_keyboard: Option<OnScreenKeyboard>,
+    #[cfg(feature = "gamepad")]
+    gamepad_navigation: Option<GamepadNavigation>,
     context: Context,
     raw_input: egui::RawInput,
     modifier_state: ModifiersState,
     pointer_pos: Option<egui::Pos2>,
+    // The last pointer position on the egui surface and the mouse buttons egui saw pressed, so
+    // that buttons released outside of the surface are released in egui as well.
+    last_pointer_pos: egui::Pos2,
+    pressed_buttons: Vec<egui::PointerButton>,
+    // The part of the background layer of the previous frame that isn't covered by panels.
+    unused_rect: egui::Rect,
+
+    hide_cursor_while_typing: bool,
+    cursor_hidden_by_typing: bool,
+    cursor_idle_timeout: Option<std::time::Duration>,
+    // Clock time of the last mouse input, for hiding the cursor when it is idle.
+    last_pointer_activity: f64,
+    software_cursor: Option<SoftwareCursor>,
+    click_through: bool,
+    // The cursor hit test state that was last applied to the window.
+    applied_cursor_hittest: Option<bool>,
+
+    theme: Option<ThemePreference>,
+    system_theme: Option<Theme>,
+    dark_visuals: egui::Visuals,
+    light_visuals: egui::Visuals,
 
     #[cfg(feature = "clipboard")]
     clipboard: Option<ClipboardContext>,
 
+    #[cfg(feature = "accesskit")]
+    accesskit: Option<accesskit_winit::Adapter>,
+
+    #[cfg(feature = "persistence")]
+    storage: Option<Box<dyn Storage>>,
+    #[cfg(feature = "persistence")]
+    autosave_interval: std::time::Duration,
+    #[cfg(feature = "persistence")]
+    // Clock time of the last save.
+    last_save: f64,
+
     // For emulating pointer events from touch events we merge multi-touch
     // pointers, and ref-count the press state.
     touch_pointer_pressed: u32,
@@ -75,6 +404,18 @@ pub struct Platform {
     // device IDs are opaque, so we have to create our own ID mapping.
     device_indices: HashMap<winit::event::DeviceId, u64>,
     next_device_index: u64,
+
+    // Last positions of the touches that started on the egui surface.
+    active_touches: HashMap<(u64, u64), Pos2>,
+
+    lifecycle_state: LifecycleState,
+    texture_reupload_required: bool,
+    // The renderer lost the font texture while suspended, so it has to be sent again.
+    resend_font_texture: bool,
+    memory_warning: bool,
+
+    // Only kept when damage tracking is used.
+    previous_frame: Option<PreviousFrame>,
 }
 
 impl Platform {
@@ -84,33 +425,234 @@ impl Platform {
 
         context.set_fonts(descriptor.font_definitions.clone());
         context.set_style(descriptor.style);
-        let raw_input = egui::RawInput {
-            screen_rect: Some(egui::Rect::from_min_size(
-                Pos2::default(),
-                vec2(
-                    descriptor.physical_width as f32,
-                    descriptor.physical_height as f32,
-                ) / descriptor.scale_factor as f32,
-            )),
-            ..Default::default()
-        };
-
-        Self {
+        // The zoom shortcuts are handled by the platform, which has to know the zoom factor.
+        context.options_mut(|options| options.zoom_with_keyboard = false);
+        let mut platform = Self {
             scale_factor: descriptor.scale_factor,
+            pending_zoom_factor: None,
+            zoom_limits: (0.2, 5.0),
+            zoom_with_keyboard: true,
+            physical_size: PhysicalSize::new(descriptor.physical_width, descriptor.physical_height),
+            target_rect: descriptor.target_rect,
+            pointer_transform: None,
+            scroll_config: descriptor.scroll_config,
+            renderer_capabilities: descriptor.renderer_capabilities,
+            input_filters: Vec::new(),
+            user_event_handler: None,
+            injected_events: Default::default(),
+            clock: Box::new(SystemClock::new()),
+            last_frame_time: None,
+            refresh_rate: None,
+            errors: VecDeque::new(),
+            error_callback: None,
+            wants_text_input: false,
+            applied_text_input: None,
+            text_input_callback: None,
+            on_screen_keyboard: None,
+            #[cfg(feature = "gamepad")]
+            gamepad_navigation: None,
             context,
-            raw_input,
+            raw_input: egui::RawInput::default(),
             modifier_state: winit::keyboard::ModifiersState::empty(),
             pointer_pos: Some(Pos2::default()),
+            last_pointer_pos: Pos2::default(),
+            pressed_buttons: Vec::new(),
+            unused_rect: egui::Rect::EVERYTHING,
+            hide_cursor_while_typing: false,
+            cursor_hidden_by_typing: false,
+            cursor_idle_timeout: None,
+            last_pointer_activity: 0.0,
+            software_cursor: None,
+            click_through: false,
+            applied_cursor_hittest: None,
+            theme: descriptor.theme,
+            system_theme: None,
+            dark_visuals: descriptor.dark_visuals.unwrap_or_else(egui::Visuals::dark),
+            light_visuals: descriptor.light_visuals.unwrap_or_else(egui::Visuals::light),
             #[cfg(feature = "clipboard")]
             clipboard: ClipboardContext::new().ok(),
+            #[cfg(feature = "accesskit")]
+            accesskit: None,
+            #[cfg(feature = "persistence")]
+            storage: None,
+            #[cfg(feature = "persistence")]
+            autosave_interval: std::time::Duration::from_secs(30),
+            #[cfg(feature = "persistence")]
+            last_save: 0.0,
             touch_pointer_pressed: 0,
             device_indices: HashMap::new(),
             next_device_index: 1,
+            active_touches: HashMap::new(),
+            lifecycle_state: LifecycleState::Running,
+            texture_reupload_required: false,
+            resend_font_texture: false,
+            memory_warning: false,
+            previous_frame: None,
+        };
+        platform.update_screen_rect();
+        platform.apply_theme();
+        platform
+    }
+
+    /// Creates a new `Platform` for the given window. The size, scale factor, theme and focus
+    /// state are read from the window and override the values of the `descriptor`.
+    pub fn from_window(window: &winit::window::Window, descriptor: PlatformDescriptor) -> Self {
+        let size = window.inner_size();
+        let mut platform = Self::new(PlatformDescriptor {
+            physical_width: size.width,
+            physical_height: size.height,
+            scale_factor: window.scale_factor(),
+            ..descriptor
+        });
+
+        platform.system_theme = window.theme();
+        platform.apply_theme();
+        platform.raw_input.focused = window.has_focus();
+        platform.refresh_rate = window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+            .map(|millihertz| millihertz as f32 / 1000.0);
+
+        window.set_ime_allowed(false);
+        platform.applied_text_input = Some(false);
+        window.set_cursor_visible(true);
+        window.set_cursor_icon(CursorIcon::Default);
+
+        platform
+    }
+
+    /// Uses the given storage to persist the egui memory. The previously saved memory is loaded
+    /// here and not in [`Self::new`], so chain this directly after creating the `Platform`,
+    /// before the first frame. The options of the context, like the style and the zoom factor,
+    /// are kept and not taken from the saved memory.
+    #[cfg(feature = "persistence")]
+    pub fn with_storage(mut self, mut storage: impl Storage + 'static) -> Self {
+        match storage.load() {
+            Ok(Some(memory)) => {
+                // The style isn't saved, so the loaded options would reset it to the default.
+                self.context.memory_mut(|m| {
+                    let options = std::mem::take(&mut m.options);
+                    *m = memory;
+                    m.options = options;
+                });
+                self.apply_theme();
+            }
+            Ok(None) => {}
+            Err(err) => self.report_error(PlatformError::Persistence(err)),
+        }
+        self.storage = Some(Box::new(storage));
+        self.last_save = self.clock.now();
+        self
+    }
+
+    /// Sets the interval in which the egui memory is saved automatically. Defaults to 30 seconds.
+    #[cfg(feature = "persistence")]
+    pub fn set_autosave_interval(&mut self, interval: std::time::Duration) {
+        self.autosave_interval = interval;
+    }
+
+    /// Saves the egui memory to the storage. This is done automatically in the autosave
+    /// interval and when the window is closed.
+    #[cfg(feature = "persistence")]
+    pub fn save(&mut self) {
+        if let Some(storage) = self.storage.as_mut() {
+            if let Err(err) = self.context.memory(|memory| storage.save(memory)) {
+                self.report_error(PlatformError::Persistence(err));
+            }
         }
+        self.last_save = self.clock.now();
+    }
+
+    /// Sets a callback that is called for every error the `Platform` encounters. Errors are
+    /// still passed to the `log` or `tracing` backend if enabled, but no longer written to
+    /// stderr.
+    pub fn set_error_callback(&mut self, callback: impl FnMut(&PlatformError) + 'static) {
+        self.error_callback = Some(Box::new(callback));
+    }
+
+    /// Returns the errors collected since the last call. Only the most recent errors are kept.
+    pub fn drain_errors(&mut self) -> impl Iterator<Item = PlatformError> + '_ {
+        self.errors.drain(..)
+    }
+
+    fn report_error(&mut self, error: PlatformError) {
+        log_error(&error, self.error_callback.is_some());
+
+        if let Some(callback) = self.error_callback.as_mut() {
+            callback(&error);
+        }
+
+        if self.errors.len() == MAX_QUEUED_ERRORS {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(error);
     }
 
     /// Handles the given winit event and updates the egui context. Should be called before starting a new frame with `start_frame()`.
-    pub fn handle_event<T>(&mut self, winit_event: &Event<T>) {
+    ///
+    /// Returns whether egui consumed the event and whether a redraw is needed. Consumption is
+    /// decided with the hit-testing of the previous frame and the current keyboard focus.
+    pub fn handle_event<T: 'static>(&mut self, winit_event: &Event<T>) -> EventResponse {
+        let events_before = self.raw_input.events.len();
+        self.process_event(winit_event);
+        let user_event_repaint = match winit_event {
+            Event::UserEvent(user_event) => {
+                self.handle_user_event(user_event) || injector::has_events(&self.injected_events)
+            }
+            _ => false,
+        };
+        self.apply_input_filters(events_before);
+
+        let mut response = EventResponse {
+            consumed: false,
+            repaint: user_event_repaint || self.raw_input.events.len() != events_before,
+        };
+        if let Event::WindowEvent { event, .. } = winit_event {
+            response.consumed = self.consumes_event(event);
+            response.repaint |= matches!(
+                event,
+                Resized(_)
+                    | ScaleFactorChanged { .. }
+                    | ThemeChanged(_)
+                    | ModifiersChanged(_)
+                    | Focused(_)
+            );
+        }
+        response.repaint |= matches!(winit_event, Event::Resumed);
+        response
+    }
+
+    /// Sets a handler for the user events of the event loop. It is called with every
+    /// `Event::UserEvent` and can push egui events into the given vector, which are passed through
+    /// the input filters like all other input. Return `true` to request a repaint without
+    /// producing any input, e.g. when a background worker has new data to show.
+    ///
+    /// `T` has to be the user event type of the event loop, otherwise the handler is never called.
+    pub fn set_user_event_handler<T: 'static>(
+        &mut self,
+        handler: impl FnMut(&T, &mut Vec<egui::Event>) -> bool + 'static,
+    ) {
+        let handler: UserEventHandler<T> = Box::new(handler);
+        self.user_event_handler = Some(Box::new(handler));
+    }
+
+    /// Returns a handle to queue egui events from other threads.
+    pub fn injector(&self) -> InputInjector {
+        InputInjector::new(self.injected_events.clone())
+    }
+
+    fn handle_user_event<T: 'static>(&mut self, user_event: &T) -> bool {
+        match self
+            .user_event_handler
+            .as_mut()
+            .and_then(|handler| handler.downcast_mut::<UserEventHandler<T>>())
+        {
+            Some(handler) => handler(user_event, &mut self.raw_input.events),
+            None => false,
+        }
+    }
+
+    fn process_event<T>(&mut self, winit_event: &Event<T>) {
         match winit_event {
             Event::WindowEvent {
                 window_id: _window_id,
@@ -125,53 +667,64 @@ impl Platform {
                     height: 0,
                 }) => {}
                 Resized(physical_size) => {
-                    self.raw_input.screen_rect = Some(egui::Rect::from_min_size(
-                        Default::default(),
-                        vec2(physical_size.width as f32, physical_size.height as f32)
-                            / self.scale_factor as f32,
-                    ));
+                    self.physical_size = *physical_size;
+                    self.update_screen_rect();
                 }
                 ScaleFactorChanged {
                     scale_factor,
                     inner_size_writer: _,
                 } => {
                     self.scale_factor = *scale_factor;
+                    self.update_screen_rect();
+                }
+                #[cfg(feature = "persistence")]
+                CloseRequested => {
+                    self.save();
+                }
+                Focused(focused) => {
+                    self.raw_input.focused = *focused;
+                }
+                ThemeChanged(theme) => {
+                    self.system_theme = Some(*theme);
+                    self.apply_theme();
                 }
                 MouseInput { state, button, .. } => {
+                    self.on_pointer_activity();
                     if let winit::event::MouseButton::Other(..) = button {
                     } else {
-                        // push event only if the cursor is inside the window
-                        if let Some(pointer_pos) = self.pointer_pos {
+                        let button = match button {
+                            winit::event::MouseButton::Left => egui::PointerButton::Primary,
+                            winit::event::MouseButton::Right => egui::PointerButton::Secondary,
+                            winit::event::MouseButton::Middle => egui::PointerButton::Middle,
+                            winit::event::MouseButton::Back => egui::PointerButton::Extra1,
+                            winit::event::MouseButton::Forward => egui::PointerButton::Extra2,
+                            winit::event::MouseButton::Other(_) => unreachable!(),
+                        };
+                        let pressed = *state == winit::event::ElementState::Pressed;
+                        // Presses are only pushed if the cursor is on the egui surface, but the
+                        // release of a press egui saw is always pushed to end drags.
+                        let pos = match self.pointer_pos {
+                            Some(pointer_pos) => Some(pointer_pos),
+                            None if !pressed && self.pressed_buttons.contains(&button) => {
+                                Some(self.last_pointer_pos)
+                            }
+                            None => None,
+                        };
+                        if let Some(pos) = pos {
+                            self.pressed_buttons.retain(|pressed| *pressed != button);
+                            if pressed {
+                                self.pressed_buttons.push(button);
+                            }
                             self.raw_input.events.push(egui::Event::PointerButton {
-                                pos: pointer_pos,
-                                button: match button {
-                                    winit::event::MouseButton::Left => egui::PointerButton::Primary,
-                                    winit::event::MouseButton::Right => {
-                                        egui::PointerButton::Secondary
-                                    }
-                                    winit::event::MouseButton::Middle => {
-                                        egui::PointerButton::Middle
-                                    }
-                                    winit::event::MouseButton::Back => {
-                                        egui::PointerButton::Extra1
-                                    },
-                                    winit::event::MouseButton::Forward => {
-                                        egui::PointerButton::Extra2
-                                    },
-                                    winit::event::MouseButton::Other(_) => unreachable!(),
-                                },
-                                pressed: *state == winit::event::ElementState::Pressed,
+                                pos,
+                                button,
+                                pressed,
                                 modifiers: Default::default(),
                             });
                         }
                     }
                 }
                 Touch(touch) => {
-                    let pointer_pos = pos2(
-                        touch.location.x as f32 / self.scale_factor as f32,
-                        touch.location.y as f32 / self.scale_factor as f32,
-                    );
-
                     let device_id = match self.device_indices.get(&touch.device_id) {
                         Some(id) => *id,
                         None => {
@@ -181,6 +734,30 @@ impl Platform {
                             device_id
                         }
                     };
+
+                    let touch_key = (device_id, touch.id);
+                    let position = self.window_to_egui_pos(touch.location);
+                    let pointer_pos = match touch.phase {
+                        // Touches that don't start on the egui surface are ignored.
+                        TouchPhase::Started => match position {
+                            Some(position) => position,
+                            None => return,
+                        },
+                        // Keep the last position if a touch leaves the egui surface.
+                        _ => match self.active_touches.get(&touch_key) {
+                            Some(last_position) => position.unwrap_or(*last_position),
+                            None => return,
+                        },
+                    };
+                    match touch.phase {
+                        TouchPhase::Started | TouchPhase::Moved => {
+                            self.active_touches.insert(touch_key, pointer_pos);
+                        }
+                        TouchPhase::Ended | TouchPhase::Cancelled => {
+                            self.active_touches.remove(&touch_key);
+                        }
+                    }
+
                     let egui_phase = match touch.phase {
                         TouchPhase::Started => egui::TouchPhase::Start,
                         TouchPhase::Moved => egui::TouchPhase::Move,
@@ -223,7 +800,7 @@ impl Platform {
                             {
                                 Some(count) => count,
                                 None => {
-                                    eprintln!("Pointer emulation error: Unbalanced touch start/stop events from Winit");
+                                    self.report_error(PlatformError::UnbalancedTouch);
                                     0
                                 }
                             };
@@ -254,14 +831,19 @@ impl Platform {
                         self.raw_input.events.push(egui::Event::PointerGone);
                     }
                 }
+                // Scrolling outside of the egui surface is not meant for egui.
+                MouseWheel { .. }
+                    if (self.target_rect.is_some() || self.pointer_transform.is_some())
+                        && self.pointer_pos.is_none() => {}
                 MouseWheel { delta, .. } => {
+                    self.on_pointer_activity();
                     let mut delta = match delta {
                         winit::event::MouseScrollDelta::LineDelta(x, y) => {
-                            let line_height = 8.0; // TODO as in egui_glium
-                            vec2(*x, *y) * line_height
+                            vec2(*x, *y) * self.scroll_config.line_height
                         }
                         winit::event::MouseScrollDelta::PixelDelta(delta) => {
                             vec2(delta.x as f32, delta.y as f32)
+                                * self.scroll_config.pixel_multiplier
                         }
                     };
                     if cfg!(target_os = "macos") {
@@ -279,14 +861,7 @@ impl Platform {
                     }
                 }
                 CursorMoved { position, .. } => {
-                    let pointer_pos = pos2(
-                        position.x as f32 / self.scale_factor as f32,
-                        position.y as f32 / self.scale_factor as f32,
-                    );
-                    self.pointer_pos = Some(pointer_pos);
-                    self.raw_input
-                        .events
-                        .push(egui::Event::PointerMoved(pointer_pos));
+                    self.on_cursor_moved(*position);
                 }
                 CursorLeft { .. } => {
                     self.pointer_pos = None;
@@ -317,6 +892,9 @@ impl Platform {
                 },
                 KeyboardInput { event, .. } => {
                     let pressed = event.state == winit::event::ElementState::Pressed;
+                    if pressed && self.hide_cursor_while_typing && self.wants_text_input {
+                        self.cursor_hidden_by_typing = true;
+                    }
                     
                     if let Some(text) = event.text.as_ref() {
                         if text.chars().all(is_printable) && !self.modifier_state.control_key() && !self.modifier_state.super_key() {
@@ -337,8 +915,13 @@ impl Platform {
                             Key::Character(c) if c.to_ascii_lowercase() == "v" => {
                                 #[cfg(feature = "clipboard")]
                                 if let Some(ref mut clipboard) = self.clipboard {
-                                    if let Ok(contents) = clipboard.get_contents() {
-                                        self.raw_input.events.push(egui::Event::Text(contents));
+                                    match clipboard.get_contents() {
+                                        Ok(contents) => {
+                                            self.raw_input.events.push(egui::Event::Paste(contents))
+                                        }
+                                        Err(err) => self.report_error(PlatformError::Clipboard(
+                                            err.to_string(),
+                                        )),
                                     }
                                 }
                                 return;
@@ -359,10 +942,302 @@ impl Platform {
                 _ => {}
             },
             Event::DeviceEvent { .. } => {}
+            Event::Suspended => {
+                self.lifecycle_state = LifecycleState::Suspended;
+                self.reset_pointer_state();
+                self.previous_frame = None;
+            }
+            Event::Resumed => {
+                if self.lifecycle_state == LifecycleState::Suspended {
+                    self.texture_reupload_required = true;
+                    self.resend_font_texture = true;
+                }
+                // The surface may have been recreated, so the next frame is drawn in full.
+                self.previous_frame = None;
+                self.lifecycle_state = LifecycleState::Running;
+            }
+            Event::MemoryWarning => {
+                self.memory_warning = true;
+            }
+            #[cfg(feature = "persistence")]
+            Event::LoopExiting => {
+                self.save();
+            }
             _ => {}
         }
     }
 
+    /// Sets the theme and updates the egui visuals accordingly.
+    pub fn set_theme(&mut self, theme: ThemePreference) {
+        self.theme = Some(theme);
+        self.apply_theme();
+    }
+
+    /// Returns the current theme preference, if the `Platform` manages the egui visuals.
+    pub fn theme(&self) -> Option<ThemePreference> {
+        self.theme
+    }
+
+    fn apply_theme(&self) {
+        let dark = match self.theme {
+            None => return,
+            Some(ThemePreference::Dark) => true,
+            Some(ThemePreference::Light) => false,
+            // Egui defaults to dark visuals if the system theme is unknown.
+            Some(ThemePreference::System) => self.system_theme != Some(Theme::Light),
+        };
+
+        if dark {
+            self.context.set_visuals(self.dark_visuals.clone());
+        } else {
+            self.context.set_visuals(self.light_visuals.clone());
+        }
+    }
+
+    /// Sets the region of the window in physical pixel egui is drawn into. Input outside of
+    /// this region is ignored. `None` uses the whole window.
+    pub fn set_target_rect(&mut self, rect: Option<egui::Rect>) {
+        self.target_rect = rect;
+        self.update_screen_rect();
+    }
+
+    /// Returns the region of the window in physical pixel egui is drawn into.
+    pub fn target_rect(&self) -> Option<egui::Rect> {
+        self.target_rect
+    }
+
+    fn update_screen_rect(&mut self) {
+        let physical_size = match self.target_rect {
+            Some(rect) => rect.size(),
+            None => vec2(
+                self.physical_size.width as f32,
+                self.physical_size.height as f32,
+            ),
+        };
+        self.raw_input.screen_rect = Some(egui::Rect::from_min_size(
+            Default::default(),
+            physical_size / self.pixels_per_point(),
+        ));
+    }
+
+    /// Sets a transform that maps window positions to egui positions. It replaces the mapping
+    /// of the target rect for all cursor, button and touch events.
+    pub fn set_pointer_transform(&mut self, transform: impl PointerTransform + 'static) {
+        self.pointer_transform = Some(Box::new(transform));
+    }
+
+    /// Removes the pointer transform, so that the target rect is used again.
+    pub fn clear_pointer_transform(&mut self) {
+        self.pointer_transform = None;
+    }
+
+    /// Converts a position in physical window pixel into a position in the egui screen rect.
+    /// Returns `None` if the position is not on the egui surface.
+    fn window_to_egui_pos(&self, position: PhysicalPosition<f64>) -> Option<Pos2> {
+        if let Some(transform) = &self.pointer_transform {
+            return transform.transform(position);
+        }
+
+        let physical = pos2(position.x as f32, position.y as f32);
+        let offset = match self.target_rect {
+            Some(rect) if !rect.contains(physical) => return None,
+            Some(rect) => rect.min.to_vec2(),
+            None => egui::Vec2::ZERO,
+        };
+        Some(((physical - offset).to_vec2() / self.pixels_per_point()).to_pos2())
+    }
+
+    /// Creates an AccessKit adapter for the given window. This must be called before the window
+    /// is shown for the first time, so create the window invisible and show it afterwards.
+    ///
+    /// Action requests of screen readers are sent as user events through the `event_loop_proxy`
+    /// and have to be passed to [`Self::on_accesskit_action_request`].
+    #[cfg(feature = "accesskit")]
+    pub fn init_accesskit<T: From<accesskit_winit::ActionRequestEvent> + Send>(
+        &mut self,
+        window: &winit::window::Window,
+        event_loop_proxy: winit::event_loop::EventLoopProxy<T>,
+    ) {
+        let context = self.context.clone();
+        self.accesskit = Some(accesskit_winit::Adapter::new(
+            window,
+            move || {
+                // The adapter only calls this once a screen reader is active.
+                context.enable_accesskit();
+                context.request_repaint();
+                context.accesskit_placeholder_tree_update()
+            },
+            event_loop_proxy,
+        ));
+    }
+
+    /// Passes a window event to the AccessKit adapter. Should be called for every window event
+    /// before [`Self::handle_event`].
+    #[cfg(feature = "accesskit")]
+    pub fn process_accesskit_event(
+        &self,
+        window: &winit::window::Window,
+        event: &winit::event::WindowEvent,
+    ) {
+        if let Some(accesskit) = &self.accesskit {
+            accesskit.process_event(window, event);
+        }
+    }
+
+    /// Forwards an action request of a screen reader to egui.
+    #[cfg(feature = "accesskit")]
+    pub fn on_accesskit_action_request(&mut self, request: egui::accesskit::ActionRequest) {
+        self.raw_input
+            .events
+            .push(egui::Event::AccessKitActionRequest(request));
+    }
+
+    /// Releases all touches and the pointer, e.g. because the application was suspended.
+    fn reset_pointer_state(&mut self) {
+        let mut last_touch_pos = None;
+        for ((device_id, id), pos) in self.active_touches.drain() {
+            self.raw_input.events.push(egui::Event::Touch {
+                device_id: egui::TouchDeviceId(device_id),
+                id: egui::TouchId(id),
+                phase: egui::TouchPhase::Cancel,
+                pos,
+                force: None,
+            });
+            last_touch_pos = Some(pos);
+        }
+
+        if self.touch_pointer_pressed > 0 {
+            self.raw_input.events.push(egui::Event::PointerButton {
+                pos: last_touch_pos.unwrap_or_default(),
+                button: egui::PointerButton::Primary,
+                pressed: false,
+                modifiers: Default::default(),
+            });
+            self.touch_pointer_pressed = 0;
+        }
+
+        for button in self.pressed_buttons.drain(..) {
+            self.raw_input.events.push(egui::Event::PointerButton {
+                pos: self.last_pointer_pos,
+                button,
+                pressed: false,
+                modifiers: Default::default(),
+            });
+        }
+
+        self.pointer_pos = None;
+        self.raw_input.events.push(egui::Event::PointerGone);
+    }
+
+    /// Returns the lifecycle state of the application.
+    pub fn lifecycle_state(&self) -> LifecycleState {
+        self.lifecycle_state
+    }
+
+    /// Returns `true` once after the application resumed from a suspension. All user textures
+    /// have to be uploaded again, the egui font texture is sent again in the `textures_delta`
+    /// of the next frame.
+    pub fn take_texture_reupload_request(&mut self) -> bool {
+        std::mem::take(&mut self.texture_reupload_required)
+    }
+
+    /// Returns `true` once after the system warned that memory is running low, e.g. on Android
+    /// and iOS. Free caches and unused textures then, before the application is terminated.
+    pub fn take_memory_warning(&mut self) -> bool {
+        std::mem::take(&mut self.memory_warning)
+    }
+
+    /// Adds a filter that is applied to all egui events produced by [`Self::handle_event`].
+    /// Filters are applied in the order they were added.
+    pub fn add_input_filter(&mut self, filter: impl InputFilter + 'static) {
+        self.input_filters.push(Box::new(filter));
+    }
+
+    /// Removes all input filters.
+    pub fn clear_input_filters(&mut self) {
+        self.input_filters.clear();
+    }
+
+    /// Runs the events produced since `first_event` through the input filters.
+    fn apply_input_filters(&mut self, first_event: usize) {
+        if self.input_filters.is_empty() {
+            return;
+        }
+
+        let mut events = self.raw_input.events.split_off(first_event);
+        for filter in &mut self.input_filters {
+            let mut filtered = Vec::with_capacity(events.len());
+            for event in events {
+                filter.filter(event, &mut filtered);
+            }
+            events = filtered;
+        }
+        self.raw_input.events.append(&mut events);
+    }
+
+    fn consumes_event(&self, event: &winit::event::WindowEvent) -> bool {
+        match event {
+            Ime(_) | KeyboardInput { .. } | ModifiersChanged(_) => {
+                self.context.wants_keyboard_input()
+            }
+            MouseWheel { .. } => self.pointer_pos.is_some_and(|pos| self.is_over_egui(pos)),
+            MouseInput { .. } => {
+                self.context.is_using_pointer()
+                    || self.pointer_pos.is_some_and(|pos| self.is_over_egui(pos))
+            }
+            CursorMoved { .. } => self.context.is_using_pointer(),
+            Touch(touch) => {
+                self.context.is_using_pointer()
+                    || self
+                        .window_to_egui_pos(touch.location)
+                        .is_some_and(|pos| self.is_over_egui(pos))
+            }
+            _ => false,
+        }
+    }
+
+    fn on_cursor_moved(&mut self, position: PhysicalPosition<f64>) {
+        self.on_pointer_activity();
+        if let Some(pointer_pos) = self.window_to_egui_pos(position) {
+            self.pointer_pos = Some(pointer_pos);
+            self.last_pointer_pos = pointer_pos;
+            self.raw_input
+                .events
+                .push(egui::Event::PointerMoved(pointer_pos));
+        } else if self.pointer_pos.take().is_some() {
+            self.raw_input.events.push(egui::Event::PointerGone);
+        }
+    }
+
+    /// Remembers the region of the frame that isn't covered by panels, like
+    /// `egui::Context::is_pointer_over_area` does for the pointer. Egui doesn't expose it, so it
+    /// is derived from the available rect, which excludes the side panels, and the frame a
+    /// central panel paints over the rest.
+    fn update_unused_rect(&mut self, shapes: &[egui::epaint::ClippedShape]) {
+        let available_rect = self.context.available_rect();
+        let central_panel = shapes.iter().any(|clipped| match &clipped.shape {
+            egui::Shape::Rect(rect) => rect.rect.contains_rect(available_rect.shrink(0.5)),
+            _ => false,
+        });
+        self.unused_rect = if central_panel {
+            egui::Rect::NOTHING
+        } else {
+            available_rect
+        };
+    }
+
+    /// Hit-tests the given position against the egui areas of the previous frame.
+    fn is_over_egui(&self, pos: Pos2) -> bool {
+        match self.context.layer_id_at(pos) {
+            // Panels live in the background layer, which also covers the unused space.
+            Some(layer) if layer.order == egui::Order::Background => {
+                !self.unused_rect.contains(pos)
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
     /// Returns `true` if egui should handle the event exclusively. Check this to
     /// avoid unexpected interactions, e.g. a mouse click registering "behind" the UI.
     pub fn captures_event<T>(&self, winit_event: &Event<T>) -> bool {
@@ -375,11 +1250,19 @@ impl Platform {
                     self.context().wants_keyboard_input()
                 }
 
-                MouseWheel { .. } | MouseInput { .. } => self.context().wants_pointer_input(),
+                MouseWheel { .. } | MouseInput { .. } => {
+                    self.pointer_pos.is_some() && self.context().wants_pointer_input()
+                }
 
-                CursorMoved { .. } => self.context().is_using_pointer(),
+                CursorMoved { position, .. } => {
+                    self.window_to_egui_pos(*position).is_some()
+                        && self.context().is_using_pointer()
+                }
 
-                Touch { .. } => self.context().is_using_pointer(),
+                Touch(touch) => {
+                    self.window_to_egui_pos(touch.location).is_some()
+                        && self.context().is_using_pointer()
+                }
 
                 _ => false,
             },
@@ -389,45 +1272,584 @@ impl Platform {
     }
 
     /// Updates the internal time for egui used for animations. `elapsed_seconds` should be the seconds since some point in time (for example application start).
+    ///
+    /// This overrides the time of the clock for the next frame.
     pub fn update_time(&mut self, elapsed_seconds: f64) {
         self.raw_input.time = Some(elapsed_seconds);
     }
 
+    /// Sets the clock that is sampled at the start of every frame. Defaults to a [`SystemClock`].
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+        self.last_frame_time = None;
+        #[cfg(feature = "persistence")]
+        {
+            self.last_save = self.clock.now();
+        }
+    }
+
+    /// Sets the refresh rate of the monitor in Hz. If known, it is used to predict the frame
+    /// time, otherwise the measured interval between frames is used.
+    pub fn set_refresh_rate(&mut self, refresh_rate: Option<f32>) {
+        self.refresh_rate = refresh_rate;
+    }
+
+    fn update_frame_timing(&mut self) {
+        let clock = &self.clock;
+        let time = *self.raw_input.time.get_or_insert_with(|| clock.now());
+
+        if let Some(refresh_rate) = self.refresh_rate.filter(|rate| *rate > 0.0) {
+            self.raw_input.predicted_dt = 1.0 / refresh_rate;
+        } else if let Some(last_frame_time) = self.last_frame_time {
+            let dt = (time - last_frame_time) as f32;
+            if dt > 0.0 {
+                self.raw_input.predicted_dt = dt.min(MAX_PREDICTED_DT);
+            }
+        }
+        self.last_frame_time = Some(time);
+    }
+
     /// Starts a new frame by providing a new `Ui` instance to write into.
     pub fn begin_frame(&mut self) {
+        self.prepare_raw_input();
+        self.context.begin_frame(self.raw_input.take());
+        self.sync_zoom_factor();
+    }
+
+    /// Takes over the zoom factor egui applied in `begin_frame`, which may also have been set by
+    /// the application with `egui::Context::set_zoom_factor` or `set_pixels_per_point`.
+    fn sync_zoom_factor(&mut self) {
+        self.pending_zoom_factor = None;
+        self.update_screen_rect();
+    }
+
+    /// Fills in the frame timing and adds the events of all other input sources.
+    fn prepare_raw_input(&mut self) {
+        let viewport_id = self.raw_input.viewport_id;
+        self.raw_input
+            .viewports
+            .entry(viewport_id)
+            .or_default()
+            .native_pixels_per_point = Some(self.scale_factor as f32);
+        self.update_frame_timing();
+        self.raw_input.max_texture_side = self.renderer_capabilities.max_texture_side;
+
+        if self.zoom_with_keyboard {
+            self.handle_zoom_shortcuts();
+        }
+
+        let first_event = self.raw_input.events.len();
+        let injected_events = injector::take_events(&self.injected_events);
+        self.raw_input.events.extend(injected_events);
+        self.apply_input_filters(first_event);
+
+        if let Some(keyboard) = self.on_screen_keyboard.as_mut() {
+            let first_event = self.raw_input.events.len();
+            self.raw_input.events.extend(keyboard.take_events());
+            self.apply_input_filters(first_event);
+        }
+
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepad_navigation) = self.gamepad_navigation.as_mut() {
+            let first_event = self.raw_input.events.len();
+            gamepad_navigation.poll(&mut self.raw_input.events);
+            self.apply_input_filters(first_event);
+        }
+    }
+
+    /// Returns the input of a frame for a UI running on a remote device, instead of starting a
+    /// frame of the local context. The zoom factor of this `Platform` is applied here, as part
+    /// of the native pixels per point that are sent to the device.
+    #[cfg(feature = "remote")]
+    pub fn take_remote_input(&mut self) -> egui::RawInput {
+        self.prepare_raw_input();
+        let viewport_id = self.raw_input.viewport_id;
+        self.raw_input
+            .viewports
+            .entry(viewport_id)
+            .or_default()
+            .native_pixels_per_point = Some(self.pixels_per_point());
+        self.raw_input.take()
+    }
+
+    /// Applies the platform output of a frame of a remote UI: sets the cursor, the text input
+    /// state, the clipboard and opens links.
+    #[cfg(feature = "remote")]
+    pub fn apply_remote_output(
+        &mut self,
+        window: Option<&winit::window::Window>,
+        platform_output: &egui::PlatformOutput,
+    ) {
+        if let Some(window) = window {
+            self.update_cursor(window, platform_output.cursor_icon);
+        }
+        self.update_text_input(window, platform_output.text_cursor_pos.is_some());
+
+        #[cfg(feature = "clipboard")]
+        if let Err(err) = handle_clipboard(platform_output, self.clipboard.as_mut()) {
+            self.report_error(err);
+        }
+
+        #[cfg(feature = "webbrowser")]
+        if let Err(err) = handle_links(platform_output) {
+            self.report_error(err);
+        }
+    }
+
+    /// Starts a new frame with the input received from a remote viewer. The screen size, scale
+    /// factor and maximum texture side of the viewer are used; the time is taken from the clock
+    /// of this `Platform`.
+    #[cfg(feature = "remote")]
+    pub fn begin_remote_frame(&mut self, input: egui::RawInput) {
+        if let Some(pixels_per_point) = input.viewport().native_pixels_per_point {
+            self.scale_factor = pixels_per_point as f64;
+        }
+        if let Some(screen_rect) = input.screen_rect {
+            let physical_size = screen_rect.size() * self.scale_factor as f32;
+            self.physical_size =
+                PhysicalSize::new(physical_size.x.round() as u32, physical_size.y.round() as u32);
+            self.update_screen_rect();
+        }
+
+        self.raw_input.modifiers = input.modifiers;
+        self.raw_input.focused = input.focused;
+        self.raw_input.hovered_files = input.hovered_files;
+        self.raw_input.dropped_files.extend(input.dropped_files);
+        let first_event = self.raw_input.events.len();
+        self.raw_input.events.extend(input.events);
+        self.apply_input_filters(first_event);
+
+        self.prepare_raw_input();
+        // The viewer draws the frame, so its renderer decides how large textures may be.
+        if input.max_texture_side.is_some() {
+            self.raw_input.max_texture_side = input.max_texture_side;
+        }
         self.context.begin_frame(self.raw_input.take());
+        self.sync_zoom_factor();
+    }
+
+    /// Sends the font texture in full with the next remote frame, e.g. when a new viewer
+    /// connected. Textures loaded by the application, like `egui::TextureHandle`s, were only
+    /// sent to the previous viewer and have to be loaded again.
+    #[cfg(feature = "remote")]
+    pub fn reset_remote_textures(&mut self) {
+        self.resend_font_texture = true;
+    }
+
+    /// Ends a frame started with [`Self::begin_remote_frame`] and tessellates it for the viewer.
+    #[cfg(feature = "remote")]
+    pub fn end_remote_frame(&mut self) -> remote::RemoteFrame {
+        let output = self.end_frame(None);
+        remote::RemoteFrame::new(&self.context, output)
     }
 
     /// Ends the frame. Returns what has happened as `Output` and gives you the draw instructions
     /// as `PaintJobs`. If the optional `window` is set, it will set the cursor key based on
     /// egui's instructions.
     pub fn end_frame(&mut self, window: Option<&winit::window::Window>) -> egui::FullOutput {
-        // otherwise the below line gets flagged by clippy if both clipboard and webbrowser features are disabled
-        #[allow(clippy::let_and_return)]
-        let output = self.context.end_frame();
+        if let Some(keyboard) = self.on_screen_keyboard.as_mut() {
+            keyboard.show(&self.context);
+        }
+
+        // Wake up to hide the cursor once it becomes idle.
+        if let Some(remaining) = self
+            .cursor_idle_remaining()
+            .filter(|remaining| *remaining > std::time::Duration::ZERO)
+        {
+            self.context.request_repaint_after(remaining);
+        }
+
+        let mut output = self.context.end_frame();
+        self.update_unused_rect(&output.shapes);
+
+        if let (Some(software_cursor), Some(pointer_pos)) =
+            (&self.software_cursor, self.pointer_pos)
+        {
+            let shapes = software_cursor.shapes(output.platform_output.cursor_icon, pointer_pos);
+            if let Some(shapes) = shapes.filter(|_| !self.cursor_hidden_by_policy()) {
+                // Added last, so the cursor is drawn on top of everything else.
+                output.shapes.extend(shapes.into_iter().map(|shape| {
+                    egui::epaint::ClippedShape {
+                        clip_rect: egui::Rect::EVERYTHING,
+                        shape,
+                    }
+                }));
+                output.platform_output.cursor_icon = egui::CursorIcon::None;
+            }
+        }
 
         if let Some(window) = window {
-            if let Some(cursor_icon) = egui_to_winit_cursor_icon(output.platform_output.cursor_icon)
-            {
-                window.set_cursor_visible(true);
-                // if the pointer is located inside the window, set cursor icon
-                if self.pointer_pos.is_some() {
-                    window.set_cursor_icon(cursor_icon);
-                }
-            } else {
-                window.set_cursor_visible(false);
+            self.update_cursor(window, output.platform_output.cursor_icon);
+            if self.click_through {
+                let hittest =
+                    self.context.is_pointer_over_area() || self.context.is_using_pointer();
+                self.update_cursor_hittest(window, hittest);
             }
         }
+        self.update_text_input(window, output.platform_output.text_cursor_pos.is_some());
+
+        if std::mem::take(&mut self.resend_font_texture) {
+            let font_image = self.context.fonts(|fonts| fonts.image());
+            output.textures_delta.set.insert(
+                0,
+                (
+                    egui::TextureId::default(),
+                    egui::epaint::ImageDelta::full(font_image, egui::TextureOptions::LINEAR),
+                ),
+            );
+        }
 
         #[cfg(feature = "clipboard")]
-        handle_clipboard(&output.platform_output, self.clipboard.as_mut());
+        if let Err(err) = handle_clipboard(&output.platform_output, self.clipboard.as_mut()) {
+            self.report_error(err);
+        }
 
         #[cfg(feature = "webbrowser")]
-        handle_links(&output.platform_output);
+        if let Err(err) = handle_links(&output.platform_output) {
+            self.report_error(err);
+        }
+
+        #[cfg(feature = "persistence")]
+        if self.clock.now() - self.last_save >= self.autosave_interval.as_secs_f64() {
+            self.save();
+        }
+
+        #[cfg(feature = "accesskit")]
+        if let Some(accesskit) = &self.accesskit {
+            if let Some(update) = output.platform_output.accesskit_update.take() {
+                accesskit.update_if_active(|| update);
+            }
+        }
 
         output
     }
 
+    /// Sets the cursor icon of the window based on egui's instructions.
+    pub(crate) fn update_cursor(&self, window: &winit::window::Window, icon: egui::CursorIcon) {
+        let icon = if self.cursor_hidden_by_policy() {
+            egui::CursorIcon::None
+        } else {
+            icon
+        };
+
+        if let Some(cursor_icon) = egui_to_winit_cursor_icon(icon) {
+            window.set_cursor_visible(true);
+            // if the pointer is located inside the window, set cursor icon
+            if self.pointer_pos.is_some() {
+                window.set_cursor_icon(cursor_icon);
+            }
+        } else {
+            window.set_cursor_visible(false);
+        }
+    }
+
+    /// Hides the cursor when a key is pressed while a text field has focus, until the cursor is
+    /// moved again. Disabled by default.
+    pub fn set_hide_cursor_while_typing(&mut self, enabled: bool) {
+        self.hide_cursor_while_typing = enabled;
+        self.cursor_hidden_by_typing &= enabled;
+    }
+
+    /// Hides the cursor when the mouse was not used for the given time, until it is moved again.
+    /// `None` disables hiding, which is the default.
+    pub fn set_cursor_idle_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.cursor_idle_timeout = timeout;
+        self.last_pointer_activity = self.clock.now();
+    }
+
+    fn on_pointer_activity(&mut self) {
+        self.cursor_hidden_by_typing = false;
+        if self.cursor_idle_timeout.is_some() {
+            self.last_pointer_activity = self.clock.now();
+        }
+    }
+
+    fn cursor_hidden_by_policy(&self) -> bool {
+        self.cursor_hidden_by_typing
+            || self.cursor_idle_remaining() == Some(std::time::Duration::ZERO)
+    }
+
+    /// Sets the registry of cursor images drawn by egui instead of the system cursor.
+    /// `None` only uses the system cursor, which is the default.
+    pub fn set_software_cursor(&mut self, software_cursor: Option<SoftwareCursor>) {
+        self.software_cursor = software_cursor;
+    }
+
+    /// Returns the software cursor, e.g. to register images or select a custom cursor.
+    pub fn software_cursor_mut(&mut self) -> Option<&mut SoftwareCursor> {
+        self.software_cursor.as_mut()
+    }
+
+    /// Returns the time until the idle cursor is hidden, or `None` if no timeout is set.
+    fn cursor_idle_remaining(&self) -> Option<std::time::Duration> {
+        let timeout = self.cursor_idle_timeout?;
+        let idle =
+            std::time::Duration::try_from_secs_f64(self.clock.now() - self.last_pointer_activity)
+                .unwrap_or_default();
+        Some(timeout.saturating_sub(idle))
+    }
+
+    /// Sets a callback that is called with `true` when a text field gains focus and with `false`
+    /// when it loses focus. It replaces the default behavior of allowing IME input on the window,
+    /// which raises the soft keyboard on supported platforms, e.g. for kiosks with their own
+    /// on-screen keyboard.
+    pub fn set_text_input_callback(&mut self, callback: impl FnMut(bool) + 'static) {
+        self.text_input_callback = Some(Box::new(callback));
+    }
+
+    /// Returns `true` if a text field had focus in the last frame.
+    pub fn wants_text_input(&self) -> bool {
+        self.wants_text_input
+    }
+
+    /// Sets a keyboard drawn with egui that is shown while a widget wants text input, like a
+    /// focused `TextEdit`. `None` removes it.
+    pub fn set_on_screen_keyboard(&mut self, keyboard: Option<OnScreenKeyboard>) {
+        self.on_screen_keyboard = keyboard;
+    }
+
+    /// Returns the on-screen keyboard, e.g. to change its layout.
+    pub fn on_screen_keyboard_mut(&mut self) -> Option<&mut OnScreenKeyboard> {
+        self.on_screen_keyboard.as_mut()
+    }
+
+    /// Sets the gamepad input used to navigate the UI. `None` removes it.
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad_navigation(&mut self, gamepad_navigation: Option<GamepadNavigation>) {
+        self.gamepad_navigation = gamepad_navigation;
+    }
+
+    /// Returns the gamepad navigation, e.g. to change its bindings.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_navigation_mut(&mut self) -> Option<&mut GamepadNavigation> {
+        self.gamepad_navigation.as_mut()
+    }
+
+    fn update_text_input(&mut self, window: Option<&winit::window::Window>, active: bool) {
+        self.wants_text_input = active;
+        if self.applied_text_input == Some(active) {
+            return;
+        }
+
+        if let Some(callback) = self.text_input_callback.as_mut() {
+            callback(active);
+        } else if let Some(window) = window {
+            window.set_ime_allowed(active);
+        } else {
+            // Try again once a window is given.
+            return;
+        }
+        self.applied_text_input = Some(active);
+    }
+
+    /// Lets clicks outside of the egui areas pass through the window to the application below,
+    /// e.g. for a transparent overlay created with [`overlay_window_builder`]. The hit test of
+    /// the window is updated in every [`Self::end_frame`] that is given the window.
+    ///
+    /// A window that ignores the cursor doesn't receive cursor events on Windows, macOS, X11 and
+    /// Wayland, so the `Platform` can't see the cursor enter an egui area again by itself. While
+    /// click-through is enabled, poll the cursor position of the operating system in every frame
+    /// and pass it to [`Self::set_screen_cursor_position`], and keep the event loop running.
+    /// Wayland doesn't expose the cursor position outside of a window, so there the window stays
+    /// in pass-through once the cursor left the egui areas.
+    pub fn set_click_through(&mut self, window: &winit::window::Window, enabled: bool) {
+        self.click_through = enabled;
+        if !enabled {
+            self.update_cursor_hittest(window, true);
+        }
+    }
+
+    /// Moves the egui pointer to the given position in physical window pixel, like a
+    /// `WindowEvent::CursorMoved` event.
+    pub fn set_cursor_position(&mut self, position: PhysicalPosition<f64>) {
+        self.on_cursor_moved(position);
+    }
+
+    /// Moves the egui pointer to the given position in physical screen pixel, e.g. the cursor
+    /// position polled from the operating system while the window ignores the cursor. Positions
+    /// outside of the window remove the pointer.
+    pub fn set_screen_cursor_position(
+        &mut self,
+        window: &winit::window::Window,
+        position: PhysicalPosition<f64>,
+    ) {
+        let origin = match window.inner_position() {
+            Ok(origin) => origin,
+            Err(err) => {
+                self.report_error(PlatformError::Window(err.to_string()));
+                return;
+            }
+        };
+        let position =
+            PhysicalPosition::new(position.x - origin.x as f64, position.y - origin.y as f64);
+        let inside = (0.0..self.physical_size.width as f64).contains(&position.x)
+            && (0.0..self.physical_size.height as f64).contains(&position.y);
+        if inside {
+            self.on_cursor_moved(position);
+        } else if self.pointer_pos.take().is_some() {
+            self.raw_input.events.push(egui::Event::PointerGone);
+        }
+    }
+
+    fn update_cursor_hittest(&mut self, window: &winit::window::Window, hittest: bool) {
+        if self.applied_cursor_hittest == Some(hittest) {
+            return;
+        }
+        self.applied_cursor_hittest = Some(hittest);
+        if let Err(err) = window.set_cursor_hittest(hittest) {
+            self.report_error(PlatformError::Window(err.to_string()));
+        }
+    }
+
+    /// Ends the frame like [`Self::end_frame`], but also compares the clipped shapes and texture
+    /// deltas with the previous frame to find the regions of the screen that need to be redrawn.
+    pub fn end_frame_with_damage(
+        &mut self,
+        window: Option<&winit::window::Window>,
+    ) -> (egui::FullOutput, FrameDamage) {
+        let screen_rect = self.context.screen_rect();
+        let output = self.end_frame(window);
+
+        let frame = PreviousFrame {
+            screen_rect,
+            pixels_per_point: output.pixels_per_point,
+            target_rect: self.target_rect,
+            shapes: output.shapes.clone(),
+        };
+        let damage = match &self.previous_frame {
+            // The whole surface is redrawn if it was resized, scaled or moved.
+            Some(previous)
+                if previous.screen_rect == frame.screen_rect
+                    && previous.pixels_per_point == frame.pixels_per_point
+                    && previous.target_rect == frame.target_rect =>
+            {
+                compute_damage(&previous.shapes, &frame.shapes, &output.textures_delta)
+            }
+            _ => FrameDamage::Dirty(vec![screen_rect]),
+        };
+        self.previous_frame = Some(frame);
+
+        (output, damage)
+    }
+
+    /// Ends the frame like [`Self::end_frame`] and tessellates the shapes with the
+    /// pixels per point egui used for this frame.
+    pub fn end_frame_tessellated(
+        &mut self,
+        window: Option<&winit::window::Window>,
+    ) -> PlatformFrame {
+        let output = self.end_frame(window);
+        let clipped_primitives = self
+            .context
+            .tessellate(output.shapes, output.pixels_per_point);
+
+        PlatformFrame {
+            clipped_primitives,
+            textures_delta: output.textures_delta,
+            platform_output: output.platform_output,
+            physical_size: self.physical_size,
+            pixels_per_point: output.pixels_per_point,
+            target_color_space: self.renderer_capabilities.target_color_space,
+        }
+    }
+
+    /// Updates the capabilities of the renderer, e.g. after switching to another renderer.
+    /// They are applied at the start of the next frame. See
+    /// [`RendererCapabilities::max_texture_side`] for switching back to egui's default.
+    pub fn set_renderer_capabilities(&mut self, capabilities: RendererCapabilities) {
+        self.renderer_capabilities = capabilities;
+    }
+
+    /// Returns the capabilities of the renderer.
+    pub fn renderer_capabilities(&self) -> RendererCapabilities {
+        self.renderer_capabilities
+    }
+
+    /// Sets the zoom factor of the whole UI, on top of the scale factor of the window.
+    /// The factor is clamped to the zoom limits and applied in the next frame. NaN is ignored.
+    pub fn set_zoom_factor(&mut self, zoom_factor: f32) {
+        if zoom_factor.is_nan() {
+            return;
+        }
+        let (min, max) = self.zoom_limits;
+        let zoom_factor = zoom_factor.clamp(min, max);
+        self.pending_zoom_factor = Some(zoom_factor);
+        self.context.set_zoom_factor(zoom_factor);
+        self.update_screen_rect();
+    }
+
+    /// Returns the zoom factor of the whole UI, including a factor that is applied in the next
+    /// frame. It is the zoom factor of the egui context, so changes made through the context
+    /// are included after the next `begin_frame`. Defaults to 1.
+    pub fn zoom_factor(&self) -> f32 {
+        self.pending_zoom_factor
+            .unwrap_or_else(|| self.context.zoom_factor())
+    }
+
+    /// Sets the smallest and largest zoom factor. Defaults to 0.2 and 5. Limits given in the
+    /// wrong order are swapped, limits that aren't positive and finite are ignored.
+    pub fn set_zoom_limits(&mut self, min: f32, max: f32) {
+        let valid = |limit: f32| limit.is_finite() && limit > 0.0;
+        if !valid(min) || !valid(max) {
+            return;
+        }
+        self.zoom_limits = (min.min(max), min.max(max));
+        self.set_zoom_factor(self.zoom_factor());
+    }
+
+    /// Enables or disables zooming with Ctrl+Plus, Ctrl+Minus and Ctrl+0 (Cmd on macOS).
+    /// Enabled by default.
+    pub fn set_zoom_with_keyboard(&mut self, enabled: bool) {
+        self.zoom_with_keyboard = enabled;
+    }
+
+    /// Applies the zoom shortcuts of the queued key events and removes them.
+    fn handle_zoom_shortcuts(&mut self) {
+        let current_zoom_factor = self.zoom_factor();
+        let mut zoom_factor = current_zoom_factor;
+        self.raw_input.events.retain(|event| match event {
+            // Shift is ignored, as it is needed to type a plus on many keyboard layouts.
+            egui::Event::Key {
+                key,
+                pressed,
+                modifiers,
+                ..
+            } if modifiers.command && !modifiers.alt => {
+                let step = match key {
+                    egui::Key::PlusEquals => ZOOM_STEP,
+                    egui::Key::Minus => -ZOOM_STEP,
+                    egui::Key::Num0 => 1.0 - zoom_factor,
+                    _ => return true,
+                };
+                if *pressed {
+                    zoom_factor = ((zoom_factor + step) * 10.0).round() / 10.0;
+                }
+                false
+            }
+            _ => true,
+        });
+
+        if zoom_factor != current_zoom_factor {
+            self.set_zoom_factor(zoom_factor);
+        }
+    }
+
+    /// Returns the physical pixels per egui point, the product of the scale factor and the zoom
+    /// factor.
+    pub fn pixels_per_point(&self) -> f32 {
+        self.scale_factor as f32 * self.zoom_factor()
+    }
+
+    /// Returns the current HiDPI scale factor of the window.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Returns the current size of the window in physical pixel.
+    pub fn physical_size(&self) -> PhysicalSize<u32> {
+        self.physical_size
+    }
+
     /// Returns the internal egui context.
     pub fn context(&self) -> Context {
         self.context.clone()
@@ -440,6 +1862,87 @@ impl Platform {
     }
 }
 
+/// The last frame of [`Platform::end_frame_with_damage`], which the next frame is compared to.
+struct PreviousFrame {
+    screen_rect: egui::Rect,
+    pixels_per_point: f32,
+    target_rect: Option<egui::Rect>,
+    shapes: Vec<egui::epaint::ClippedShape>,
+}
+
+/// Collects the bounding rectangles of all shapes that differ between two frames.
+fn compute_damage(
+    previous: &[egui::epaint::ClippedShape],
+    current: &[egui::epaint::ClippedShape],
+    textures_delta: &egui::TexturesDelta,
+) -> FrameDamage {
+    let changed_textures: Vec<egui::TextureId> = textures_delta
+        .set
+        .iter()
+        .map(|(id, _)| *id)
+        .chain(textures_delta.free.iter().copied())
+        .collect();
+
+    let mut dirty = Vec::new();
+    for index in 0..previous.len().max(current.len()) {
+        let old = previous.get(index);
+        let new = current.get(index);
+
+        let texture_changed =
+            new.is_some_and(|new| uses_texture(&new.shape, &changed_textures));
+        if old == new && !texture_changed {
+            continue;
+        }
+
+        for clipped in old.into_iter().chain(new) {
+            let rect = clipped
+                .shape
+                .visual_bounding_rect()
+                .intersect(clipped.clip_rect);
+            if rect.is_positive() {
+                dirty.push(rect);
+            }
+        }
+    }
+
+    if dirty.is_empty() {
+        FrameDamage::Unchanged
+    } else {
+        FrameDamage::Dirty(merge_rects(dirty))
+    }
+}
+
+/// Returns `true` if the shape, or any shape nested in it, draws one of the given textures.
+fn uses_texture(shape: &egui::Shape, textures: &[egui::TextureId]) -> bool {
+    match shape {
+        egui::Shape::Vec(shapes) => shapes.iter().any(|shape| uses_texture(shape, textures)),
+        shape => textures.contains(&shape.texture_id()),
+    }
+}
+
+/// Merges overlapping rectangles, so that renderers don't draw the same region twice.
+fn merge_rects(mut rects: Vec<egui::Rect>) -> Vec<egui::Rect> {
+    let mut merged = true;
+    while merged {
+        merged = false;
+        let mut i = 0;
+        while i < rects.len() {
+            let mut j = i + 1;
+            while j < rects.len() {
+                if rects[i].intersects(rects[j]) {
+                    let other = rects.swap_remove(j);
+                    rects[i] = rects[i].union(other);
+                    merged = true;
+                } else {
+                    j += 1;
+                }
+            }
+            i += 1;
+        }
+    }
+    rects
+}
+
 /// Translates winit to egui keycodes.
 #[inline]
 fn winit_to_egui_key_code(key: Key) -> Option<egui::Key> {
@@ -469,6 +1972,8 @@ fn winit_to_egui_key_code(key: Key) -> Option<egui::Key> {
         Key::Character(c) if c == "8" => egui::Key::Num8,
         Key::Character(c) if c == "9" => egui::Key::Num9,
         Key::Character(c) if c == "0" => egui::Key::Num0,
+        Key::Character(c) if c == "+" || c == "=" => egui::Key::PlusEquals,
+        Key::Character(c) if c == "-" => egui::Key::Minus,
         Key::Character(c) if c.to_ascii_lowercase() == "a" => egui::Key::A,
         Key::Character(c) if c.to_ascii_lowercase() == "b" => egui::Key::B,
         Key::Character(c) if c.to_ascii_lowercase() == "c" => egui::Key::C,
@@ -571,3 +2076,484 @@ fn is_printable(chr: char) -> bool {
 
     !is_in_private_use_area && !chr.is_ascii_control()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect_shape(min: Pos2) -> egui::epaint::ClippedShape {
+        egui::epaint::ClippedShape {
+            clip_rect: egui::Rect::EVERYTHING,
+            shape: egui::Shape::rect_filled(
+                egui::Rect::from_min_size(min, vec2(10.0, 10.0)),
+                0.0,
+                egui::Color32::RED,
+            ),
+        }
+    }
+
+    #[test]
+    fn identical_frame_is_unchanged() {
+        let shapes = vec![rect_shape(pos2(0.0, 0.0)), rect_shape(pos2(50.0, 50.0))];
+        let damage = compute_damage(&shapes, &shapes, &Default::default());
+        assert_eq!(damage, FrameDamage::Unchanged);
+    }
+
+    #[test]
+    fn moved_shape_damages_old_and_new_position() {
+        let previous = vec![rect_shape(pos2(0.0, 0.0))];
+        let current = vec![rect_shape(pos2(100.0, 0.0))];
+        let damage = compute_damage(&previous, &current, &Default::default());
+
+        let FrameDamage::Dirty(rects) = damage else {
+            panic!("expected damage, got {:?}", damage);
+        };
+        assert_eq!(rects.len(), 2);
+        assert!(rects.iter().any(|rect| rect.contains(pos2(5.0, 5.0))));
+        assert!(rects.iter().any(|rect| rect.contains(pos2(105.0, 5.0))));
+    }
+
+    #[test]
+    fn texture_update_in_nested_shape_is_damage() {
+        let texture = egui::TextureId::User(1);
+        let image = egui::Shape::image(
+            texture,
+            egui::Rect::from_min_size(pos2(20.0, 20.0), vec2(10.0, 10.0)),
+            egui::Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        let shapes = vec![egui::epaint::ClippedShape {
+            clip_rect: egui::Rect::EVERYTHING,
+            shape: egui::Shape::Vec(vec![image]),
+        }];
+        let mut textures_delta = egui::TexturesDelta::default();
+        textures_delta.set.push((
+            texture,
+            egui::epaint::ImageDelta::full(
+                egui::ColorImage::new([1, 1], egui::Color32::WHITE),
+                egui::TextureOptions::LINEAR,
+            ),
+        ));
+
+        let damage = compute_damage(&shapes, &shapes, &textures_delta);
+        assert!(matches!(damage, FrameDamage::Dirty(rects) if rects.len() == 1));
+    }
+
+    #[test]
+    fn scale_or_target_rect_change_damages_the_whole_screen() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        let run_frame = |platform: &mut Platform| {
+            platform.begin_frame();
+            egui::Window::new("Window").show(&platform.context(), |ui| ui.label("Label"));
+            let (_, damage) = platform.end_frame_with_damage(None);
+            (damage, platform.context().screen_rect())
+        };
+        let is_full = |(damage, screen_rect): (FrameDamage, egui::Rect)| {
+            damage == FrameDamage::Dirty(vec![screen_rect])
+        };
+
+        run_frame(&mut platform);
+        run_frame(&mut platform);
+        assert_eq!(run_frame(&mut platform).0, FrameDamage::Unchanged);
+
+        // Twice the pixels for the same logical screen rect.
+        platform.set_zoom_factor(2.0);
+        platform.handle_event(&window_event(Resized(PhysicalSize::new(1600, 1200))));
+        assert!(is_full(run_frame(&mut platform)));
+        run_frame(&mut platform);
+        let (damage, screen_rect) = run_frame(&mut platform);
+        assert_eq!(damage, FrameDamage::Unchanged);
+        assert_eq!(screen_rect.size(), vec2(800.0, 600.0));
+
+        platform.set_target_rect(Some(egui::Rect::from_min_size(
+            pos2(100.0, 0.0),
+            vec2(1600.0, 1200.0),
+        )));
+        assert!(is_full(run_frame(&mut platform)));
+    }
+
+    #[test]
+    fn merge_rects_merges_only_overlapping_rects() {
+        let rect = |x: f32, y: f32| egui::Rect::from_min_size(pos2(x, y), vec2(10.0, 10.0));
+        let merged = merge_rects(vec![rect(0.0, 0.0), rect(5.0, 5.0), rect(100.0, 100.0)]);
+
+        assert_eq!(merged.len(), 2);
+        assert!(merged.contains(&egui::Rect::from_min_max(pos2(0.0, 0.0), pos2(15.0, 15.0))));
+        assert!(merged.contains(&rect(100.0, 100.0)));
+    }
+
+    fn window_event(event: winit::event::WindowEvent) -> Event<()> {
+        Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event,
+        }
+    }
+
+    fn cursor_moved(x: f64, y: f64) -> Event<()> {
+        window_event(CursorMoved {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            position: PhysicalPosition::new(x, y),
+        })
+    }
+
+    fn mouse_input(state: winit::event::ElementState) -> Event<()> {
+        window_event(MouseInput {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            state,
+            button: winit::event::MouseButton::Left,
+        })
+    }
+
+    fn touch(phase: TouchPhase, x: f64, y: f64) -> Event<()> {
+        window_event(Touch(winit::event::Touch {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            phase,
+            location: PhysicalPosition::new(x, y),
+            force: None,
+            id: 1,
+        }))
+    }
+
+    #[test]
+    fn touches_are_consumed_only_over_panels() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        let run_frame = |platform: &mut Platform, central_panel: bool| {
+            platform.begin_frame();
+            let context = platform.context();
+            egui::SidePanel::left("panel").show(&context, |ui| ui.label("Panel"));
+            if central_panel {
+                egui::CentralPanel::default().show(&context, |ui| ui.label("Center"));
+            }
+            platform.end_frame(None);
+        };
+        let tap = |platform: &mut Platform, x: f64| {
+            let consumed = platform
+                .handle_event(&touch(TouchPhase::Started, x, 300.0))
+                .consumed;
+            platform.handle_event(&touch(TouchPhase::Ended, x, 300.0));
+            consumed
+        };
+
+        run_frame(&mut platform, false);
+        assert!(tap(&mut platform, 20.0));
+        run_frame(&mut platform, false);
+        assert!(!tap(&mut platform, 600.0));
+        run_frame(&mut platform, false);
+        assert!(tap(&mut platform, 20.0));
+
+        run_frame(&mut platform, true);
+        assert!(tap(&mut platform, 600.0));
+    }
+
+    #[test]
+    fn release_outside_of_target_rect_ends_the_drag() {
+        let descriptor = PlatformDescriptor::new(800, 600, 1.0).with_target_rect(
+            egui::Rect::from_min_size(pos2(100.0, 100.0), vec2(200.0, 200.0)),
+        );
+        let mut platform = Platform::new(descriptor);
+
+        platform.handle_event(&cursor_moved(150.0, 150.0));
+        platform.handle_event(&mouse_input(winit::event::ElementState::Pressed));
+        platform.handle_event(&cursor_moved(500.0, 500.0));
+        platform.handle_event(&mouse_input(winit::event::ElementState::Released));
+
+        let release = egui::Event::PointerButton {
+            pos: pos2(50.0, 50.0),
+            button: egui::PointerButton::Primary,
+            pressed: false,
+            modifiers: Default::default(),
+        };
+        assert_eq!(platform.raw_input_mut().events.last(), Some(&release));
+
+        // A press and release outside of the target rect is not egui's business.
+        platform.raw_input_mut().events.clear();
+        platform.handle_event(&mouse_input(winit::event::ElementState::Pressed));
+        platform.handle_event(&mouse_input(winit::event::ElementState::Released));
+        assert!(platform.raw_input_mut().events.is_empty());
+    }
+
+    #[test]
+    fn default_descriptor_has_a_usable_scale_factor() {
+        let mut platform = Platform::new(PlatformDescriptor::default());
+        platform.begin_frame();
+        assert_eq!(platform.context().pixels_per_point(), 1.0);
+        platform.end_frame(None);
+    }
+
+    #[test]
+    fn renderer_capabilities_reach_egui_and_the_frame() {
+        let descriptor = PlatformDescriptor::new(800, 600, 1.0).with_max_texture_side(1024);
+        let mut platform = Platform::new(descriptor);
+
+        platform.begin_frame();
+        assert_eq!(platform.context().input(|i| i.max_texture_side), 1024);
+        let frame = platform.end_frame_tessellated(None);
+        assert_eq!(frame.target_color_space, TargetColorSpace::Srgb);
+
+        platform.set_renderer_capabilities(RendererCapabilities {
+            max_texture_side: Some(2048),
+            target_color_space: TargetColorSpace::Linear,
+        });
+        platform.begin_frame();
+        assert_eq!(platform.context().input(|i| i.max_texture_side), 2048);
+        let frame = platform.end_frame_tessellated(None);
+        assert_eq!(frame.target_color_space, TargetColorSpace::Linear);
+    }
+
+    #[test]
+    fn suspend_releases_touches_and_resume_resends_the_font_texture() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        // The first frame uploads the font texture anyway.
+        platform.begin_frame();
+        platform.end_frame(None);
+
+        platform.handle_event(&touch(TouchPhase::Started, 100.0, 100.0));
+        platform.raw_input_mut().events.clear();
+
+        platform.handle_event(&Event::<()>::Suspended);
+        let events = &platform.raw_input_mut().events;
+        assert!(events.iter().any(|event| matches!(
+            event,
+            egui::Event::Touch {
+                phase: egui::TouchPhase::Cancel,
+                ..
+            }
+        )));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, egui::Event::PointerButton { pressed: false, .. })));
+        assert_eq!(events.last(), Some(&egui::Event::PointerGone));
+        assert!(!platform.take_texture_reupload_request());
+
+        platform.handle_event(&Event::<()>::Resumed);
+        assert!(platform.take_texture_reupload_request());
+        assert!(!platform.take_texture_reupload_request());
+
+        platform.begin_frame();
+        let output = platform.end_frame(None);
+        assert!(output
+            .textures_delta
+            .set
+            .iter()
+            .any(|(id, delta)| *id == egui::TextureId::default() && delta.is_whole()));
+    }
+
+    #[test]
+    fn on_screen_keyboard_is_only_shown_for_text_input() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        platform.set_on_screen_keyboard(Some(OnScreenKeyboard::new(KeyboardLayout::Qwerty)));
+
+        let mut text = String::new();
+        let mut run_frame = |platform: &mut Platform, focus_text_edit: bool| {
+            platform.begin_frame();
+            egui::CentralPanel::default().show(&platform.context(), |ui| {
+                let button = ui.button("Button");
+                let text_edit = ui.text_edit_singleline(&mut text);
+                if focus_text_edit {
+                    text_edit.request_focus();
+                } else {
+                    button.request_focus();
+                }
+            });
+            platform.end_frame(None);
+            platform.on_screen_keyboard_mut().unwrap().is_visible()
+        };
+
+        run_frame(&mut platform, false);
+        assert!(!run_frame(&mut platform, false));
+        run_frame(&mut platform, true);
+        assert!(run_frame(&mut platform, true));
+        run_frame(&mut platform, false);
+        assert!(!run_frame(&mut platform, false));
+    }
+
+    #[test]
+    fn invalid_zoom_input_does_not_panic() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+
+        platform.set_zoom_limits(4.0, 0.5);
+        platform.set_zoom_factor(10.0);
+        assert_eq!(platform.zoom_factor(), 4.0);
+
+        platform.set_zoom_limits(f32::NAN, 2.0);
+        platform.set_zoom_limits(0.0, 2.0);
+        platform.set_zoom_factor(f32::NAN);
+        assert_eq!(platform.zoom_factor(), 4.0);
+    }
+
+    #[test]
+    fn zoom_factor_follows_the_context() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        platform.begin_frame();
+        platform.context().set_zoom_factor(1.5);
+        platform.end_frame(None);
+
+        platform.begin_frame();
+        assert_eq!(platform.zoom_factor(), 1.5);
+        assert_eq!(
+            platform.pixels_per_point(),
+            platform.context().pixels_per_point()
+        );
+        platform.end_frame(None);
+
+        platform.begin_frame();
+        let screen_rect = platform.context().screen_rect();
+        assert!((screen_rect.width() - 800.0 / 1.5).abs() < 0.01);
+        assert!((screen_rect.height() - 600.0 / 1.5).abs() < 0.01);
+        platform.end_frame(None);
+
+        platform.handle_event(&cursor_moved(150.0, 150.0));
+        assert_eq!(
+            platform.raw_input_mut().events.last(),
+            Some(&egui::Event::PointerMoved(pos2(100.0, 100.0)))
+        );
+    }
+
+    #[test]
+    fn cursor_idle_timeout_accepts_any_duration() {
+        let clock = ManualClock::new();
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        platform.set_clock(clock.clone());
+
+        platform.set_cursor_idle_timeout(Some(std::time::Duration::MAX));
+        clock.advance(10.0);
+        assert!(!platform.cursor_hidden_by_policy());
+
+        platform.set_cursor_idle_timeout(Some(std::time::Duration::from_secs(5)));
+        clock.advance(-1.0);
+        assert!(!platform.cursor_hidden_by_policy());
+        clock.advance(10.0);
+        assert!(platform.cursor_hidden_by_policy());
+    }
+
+    #[test]
+    fn memory_warning_is_taken_once() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        assert!(!platform.take_memory_warning());
+        platform.handle_event(&Event::<()>::MemoryWarning);
+        assert!(platform.take_memory_warning());
+        assert!(!platform.take_memory_warning());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn autosave_follows_the_clock() {
+        struct CountingStorage(std::rc::Rc<std::cell::Cell<usize>>);
+
+        impl Storage for CountingStorage {
+            fn load(&mut self) -> Result<Option<egui::Memory>, String> {
+                Ok(None)
+            }
+
+            fn save(&mut self, _memory: &egui::Memory) -> Result<(), String> {
+                self.0.set(self.0.get() + 1);
+                Ok(())
+            }
+        }
+
+        let saves = std::rc::Rc::new(std::cell::Cell::new(0));
+        let clock = ManualClock::new();
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0))
+            .with_storage(CountingStorage(saves.clone()));
+        platform.set_clock(clock.clone());
+        platform.set_autosave_interval(std::time::Duration::from_secs(30));
+
+        for (advance, expected_saves) in [(10.0, 0), (10.0, 0), (15.0, 1), (10.0, 1), (25.0, 2)] {
+            clock.advance(advance);
+            platform.begin_frame();
+            platform.end_frame(None);
+            assert_eq!(saves.get(), expected_saves);
+        }
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn loading_the_memory_keeps_style_theme_and_zoom() {
+        #[derive(Clone, Default)]
+        struct JsonStorage(std::rc::Rc<std::cell::RefCell<Option<String>>>);
+
+        impl Storage for JsonStorage {
+            fn load(&mut self) -> Result<Option<egui::Memory>, String> {
+                let json = self.0.borrow();
+                json.as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .map_err(|err| err.to_string())
+            }
+
+            fn save(&mut self, memory: &egui::Memory) -> Result<(), String> {
+                let json = serde_json::to_string(memory).map_err(|err| err.to_string())?;
+                *self.0.borrow_mut() = Some(json);
+                Ok(())
+            }
+        }
+
+        let create_platform = |storage: JsonStorage| {
+            let mut style = egui::Style::default();
+            style.spacing.item_spacing = vec2(20.0, 10.0);
+            let descriptor = PlatformDescriptor::new(800, 600, 1.0)
+                .with_style(style)
+                .with_theme(ThemePreference::Light);
+            Platform::new(descriptor).with_storage(storage)
+        };
+
+        let storage = JsonStorage::default();
+        let mut platform = create_platform(storage.clone());
+        platform.set_zoom_factor(1.5);
+        platform.begin_frame();
+        platform.end_frame(None);
+        platform.save();
+        assert!(storage.0.borrow().is_some());
+
+        let mut platform = create_platform(storage);
+        platform.begin_frame();
+        let context = platform.context();
+        assert_eq!(context.style().spacing.item_spacing, vec2(20.0, 10.0));
+        assert!(!context.style().visuals.dark_mode);
+        assert_eq!(context.zoom_factor(), 1.0);
+        assert_eq!(context.pixels_per_point(), platform.pixels_per_point());
+        assert!(!context.options(|options| options.zoom_with_keyboard));
+        platform.end_frame(None);
+    }
+
+    #[cfg(feature = "accesskit")]
+    #[test]
+    fn accesskit_tree_contains_button_and_default_action_clicks_it() {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        platform.context().enable_accesskit();
+
+        let run_frame = |platform: &mut Platform| {
+            platform.begin_frame();
+            let mut clicked = false;
+            egui::CentralPanel::default().show(&platform.context(), |ui| {
+                clicked = ui.button("Click").clicked();
+            });
+            (clicked, platform.end_frame(None))
+        };
+
+        let (_, output) = run_frame(&mut platform);
+        let update = output
+            .platform_output
+            .accesskit_update
+            .expect("no AccessKit update without an adapter");
+        let node_ids: Vec<_> = update.nodes.iter().map(|(id, _)| *id).collect();
+        let tree = accesskit_consumer::Tree::new(update, true);
+        let button_id = node_ids
+            .into_iter()
+            .find(|id| {
+                tree.state().node_by_id(*id).is_some_and(|node| {
+                    node.role() == egui::accesskit::Role::Button
+                        && node.name().as_deref() == Some("Click")
+                })
+            })
+            .expect("button is missing in the AccessKit tree");
+
+        platform.on_accesskit_action_request(egui::accesskit::ActionRequest {
+            action: egui::accesskit::Action::Default,
+            target: button_id,
+            data: None,
+        });
+        let (clicked, _) = run_frame(&mut platform);
+        assert!(clicked);
+    }
+}
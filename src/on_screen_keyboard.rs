@@ -0,0 +1,200 @@
+//! A keyboard drawn with egui for touch screens without a soft keyboard of the system.
+
+use egui::{vec2, Align2, Area, Button, Frame, Id, Order, Rect};
+
+/// The keys shown by the [`OnScreenKeyboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    /// English keyboard layout.
+    Qwerty,
+    /// French keyboard layout.
+    Azerty,
+    /// Digits only, for example for PIN entry.
+    Numeric,
+}
+
+impl KeyboardLayout {
+    fn rows(self) -> &'static [&'static str] {
+        match self {
+            KeyboardLayout::Qwerty => &["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            KeyboardLayout::Azerty => &["1234567890", "azertyuiop", "qsdfghjklm", "wxcvbn"],
+            KeyboardLayout::Numeric => &["789", "456", "123", ".0"],
+        }
+    }
+}
+
+/// A special key of the on-screen keyboard.
+#[derive(Clone, Copy)]
+enum SpecialKey {
+    Shift,
+    Space,
+    Backspace,
+    Enter,
+}
+
+/// A keyboard drawn with egui, for touch screens without a soft keyboard of the system.
+///
+/// When set with [`Platform::set_on_screen_keyboard`](crate::Platform::set_on_screen_keyboard)
+/// it is shown automatically while a widget that takes text input, like a `TextEdit`, has
+/// keyboard focus. Pressed keys are passed to egui in the next frame, in the same way as keys
+/// of a hardware keyboard.
+#[derive(Debug, Clone)]
+pub struct OnScreenKeyboard {
+    layout: KeyboardLayout,
+    key_size: f32,
+    shift: bool,
+    // The widget that receives the input. Tapping the keyboard takes the focus away from it,
+    // so it is given back after every key press.
+    target: Option<Id>,
+    last_rect: Option<Rect>,
+    events: Vec<egui::Event>,
+}
+
+impl OnScreenKeyboard {
+    /// Creates a new `OnScreenKeyboard` with the given layout.
+    pub fn new(layout: KeyboardLayout) -> Self {
+        Self {
+            layout,
+            key_size: 40.0,
+            shift: false,
+            target: None,
+            last_rect: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Sets the layout of the keyboard.
+    pub fn set_layout(&mut self, layout: KeyboardLayout) {
+        self.layout = layout;
+    }
+
+    /// Returns the layout of the keyboard.
+    pub fn layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    /// Sets the side length of a key in points. Defaults to 40.
+    pub fn set_key_size(&mut self, key_size: f32) {
+        self.key_size = key_size;
+    }
+
+    /// Returns `true` if the keyboard was shown in the last frame.
+    pub fn is_visible(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Draws the keyboard at the bottom of the screen if a widget wants text input.
+    pub(crate) fn show(&mut self, ctx: &egui::Context) {
+        // Text widgets set the text cursor position in the output while they have focus.
+        let wants_text_input = ctx.output(|output| output.text_cursor_pos.is_some());
+        let focused = ctx.memory(|memory| memory.focus());
+        let pointer_on_keyboard = match (self.last_rect, ctx.pointer_interact_pos()) {
+            (Some(rect), Some(pos)) => rect.contains(pos),
+            _ => false,
+        };
+        match focused {
+            Some(id) if wants_text_input => self.target = Some(id),
+            // The focus was taken because the keyboard itself was tapped.
+            _ if pointer_on_keyboard => {}
+            _ => self.target = None,
+        }
+
+        let target = match self.target {
+            Some(target) => target,
+            None => {
+                self.last_rect = None;
+                return;
+            }
+        };
+
+        let response = Area::new("egui_winit_platform_on_screen_keyboard")
+            .order(Order::Foreground)
+            .anchor(Align2::CENTER_BOTTOM, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| self.draw_keys(ui));
+            })
+            .response;
+        self.last_rect = Some(response.rect);
+
+        if !self.events.is_empty() {
+            ctx.memory_mut(|memory| memory.request_focus(target));
+        }
+    }
+
+    fn draw_keys(&mut self, ui: &mut egui::Ui) {
+        let key_size = vec2(self.key_size, self.key_size);
+
+        for row in self.layout.rows() {
+            ui.horizontal(|ui| {
+                for chr in row.chars() {
+                    let chr = if self.shift {
+                        chr.to_ascii_uppercase()
+                    } else {
+                        chr
+                    };
+                    if ui
+                        .add(Button::new(chr.to_string()).min_size(key_size))
+                        .clicked()
+                    {
+                        self.events.push(egui::Event::Text(chr.to_string()));
+                        self.shift = false;
+                    }
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let special_keys: &[(SpecialKey, &str, f32)] = match self.layout {
+                KeyboardLayout::Numeric => &[
+                    (SpecialKey::Backspace, "Back", 1.5),
+                    (SpecialKey::Enter, "Enter", 1.5),
+                ],
+                _ => &[
+                    (SpecialKey::Shift, "Shift", 1.5),
+                    (SpecialKey::Space, "Space", 5.0),
+                    (SpecialKey::Backspace, "Back", 1.5),
+                    (SpecialKey::Enter, "Enter", 2.0),
+                ],
+            };
+
+            for (key, label, width) in special_keys {
+                let size = vec2(key_size.x * width, key_size.y);
+                let button = Button::new(*label)
+                    .min_size(size)
+                    .selected(matches!(key, SpecialKey::Shift) && self.shift);
+                if ui.add(button).clicked() {
+                    self.press(*key);
+                }
+            }
+        });
+    }
+
+    fn press(&mut self, key: SpecialKey) {
+        let key = match key {
+            SpecialKey::Shift => {
+                self.shift = !self.shift;
+                return;
+            }
+            SpecialKey::Space => {
+                self.events.push(egui::Event::Text(" ".to_string()));
+                return;
+            }
+            SpecialKey::Backspace => egui::Key::Backspace,
+            SpecialKey::Enter => egui::Key::Enter,
+        };
+
+        for pressed in [true, false] {
+            self.events.push(egui::Event::Key {
+                key,
+                pressed,
+                repeat: false,
+                modifiers: Default::default(),
+            });
+        }
+    }
+
+    /// Returns the events of the keys pressed since the last call.
+    pub(crate) fn take_events(&mut self) -> Vec<egui::Event> {
+        std::mem::take(&mut self.events)
+    }
+}
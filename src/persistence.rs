@@ -0,0 +1,72 @@
+//! Persistence of the egui memory (window positions, collapsed headers, scroll positions, ...)
+//! across application runs.
+
+use std::path::PathBuf;
+
+/// A storage backend for the egui memory.
+pub trait Storage {
+    /// Loads the previously saved egui memory. Returns `Ok(None)` if nothing was saved yet.
+    fn load(&mut self) -> Result<Option<egui::Memory>, String>;
+
+    /// Saves the egui memory.
+    fn save(&mut self, memory: &egui::Memory) -> Result<(), String>;
+}
+
+/// The serialization format used by the [`FileStorage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Rusty Object Notation.
+    Ron,
+    /// JavaScript Object Notation.
+    Json,
+}
+
+/// Stores the egui memory in a single file.
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    path: PathBuf,
+    format: FileFormat,
+}
+
+impl FileStorage {
+    /// Creates a new `FileStorage` that reads and writes the given file.
+    pub fn new(path: impl Into<PathBuf>, format: FileFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+        }
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&mut self) -> Result<Option<egui::Memory>, String> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(format!("Failed to read {}: {}", self.path.display(), err)),
+        };
+
+        let memory = match self.format {
+            FileFormat::Ron => ron::from_str(&contents).map_err(|err| err.to_string()),
+            FileFormat::Json => serde_json::from_str(&contents).map_err(|err| err.to_string()),
+        };
+        memory
+            .map(Some)
+            .map_err(|err| format!("Failed to load egui memory: {}", err))
+    }
+
+    fn save(&mut self, memory: &egui::Memory) -> Result<(), String> {
+        let contents = match self.format {
+            FileFormat::Ron => ron::to_string(memory).map_err(|err| err.to_string()),
+            FileFormat::Json => serde_json::to_string(memory).map_err(|err| err.to_string()),
+        }
+        .map_err(|err| format!("Failed to save egui memory: {}", err))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {}: {}", parent.display(), err))?;
+        }
+        std::fs::write(&self.path, contents)
+            .map_err(|err| format!("Failed to write {}: {}", self.path.display(), err))
+    }
+}
@@ -0,0 +1,258 @@
+//! Remote operation of an egui UI over a socket.
+//!
+//! The viewer collects the input with a [`Platform`](crate::Platform) and sends it with
+//! [`Platform::take_remote_input`](crate::Platform::take_remote_input). The device runs the UI
+//! with [`Platform::begin_remote_frame`](crate::Platform::begin_remote_frame) and
+//! [`Platform::end_remote_frame`](crate::Platform::end_remote_frame) and sends back the
+//! tessellated [`RemoteFrame`], which the viewer draws with its egui renderer.
+//!
+//! Messages are sent as JSON, one per line, over any stream such as a `TcpStream` or a
+//! `UnixStream`.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A triangle mesh of a [`RemoteFrame`] with the rectangle it is clipped to, in points.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteMesh {
+    /// Clip rectangle of the mesh.
+    pub clip_rect: egui::Rect,
+    /// The mesh.
+    pub mesh: egui::Mesh,
+}
+
+/// The output of one frame of the remote UI.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RemoteFrame {
+    /// Meshes to draw. Paint callbacks can't be sent and are left out.
+    pub meshes: Vec<RemoteMesh>,
+    /// Textures that need to be uploaded before and freed after drawing.
+    pub textures_delta: egui::TexturesDelta,
+    /// Non-rendering related output of egui.
+    pub platform_output: egui::PlatformOutput,
+    /// The pixels per point that were used for tessellation.
+    pub pixels_per_point: f32,
+    /// Delay after which the UI wants to be repainted, e.g. for animations. `Duration::MAX`
+    /// if it only needs to be repainted on new input.
+    pub repaint_delay: std::time::Duration,
+}
+
+impl RemoteFrame {
+    /// Tessellates the output of a frame.
+    pub fn new(context: &egui::Context, output: egui::FullOutput) -> Self {
+        let meshes = context
+            .tessellate(output.shapes, output.pixels_per_point)
+            .into_iter()
+            .filter_map(|clipped| match clipped.primitive {
+                egui::epaint::Primitive::Mesh(mesh) => Some(RemoteMesh {
+                    clip_rect: clipped.clip_rect,
+                    mesh,
+                }),
+                egui::epaint::Primitive::Callback(_) => None,
+            })
+            .collect();
+        let repaint_delay = output
+            .viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .map_or(std::time::Duration::MAX, |viewport| viewport.repaint_delay);
+
+        Self {
+            meshes,
+            textures_delta: output.textures_delta,
+            platform_output: output.platform_output,
+            pixels_per_point: output.pixels_per_point,
+            repaint_delay,
+        }
+    }
+
+    /// Returns the meshes as clipped primitives, as expected by egui renderers.
+    pub fn clipped_primitives(&self) -> Vec<egui::ClippedPrimitive> {
+        self.meshes
+            .iter()
+            .map(|remote_mesh| egui::ClippedPrimitive {
+                clip_rect: remote_mesh.clip_rect,
+                primitive: egui::epaint::Primitive::Mesh(remote_mesh.mesh.clone()),
+            })
+            .collect()
+    }
+}
+
+/// Default for [`RemoteConnection::with_max_message_len`], in bytes.
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 256 * 1024 * 1024;
+
+/// A connection between the viewer and the device. Both sides use the same type: the viewer
+/// sends input and receives frames, the device receives input and sends frames.
+#[derive(Debug)]
+pub struct RemoteConnection<S: Read + Write> {
+    stream: BufReader<S>,
+    line: String,
+    max_message_len: usize,
+}
+
+impl<S: Read + Write> RemoteConnection<S> {
+    /// Creates a new `RemoteConnection` over the given stream.
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            line: String::new(),
+            max_message_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+
+    /// Sets the maximum length of a received message in bytes. Longer messages fail with
+    /// [`io::ErrorKind::InvalidData`] instead of being buffered, so a peer can't exhaust the
+    /// memory. The first frame carries the font atlas and needs the most space.
+    pub fn with_max_message_len(mut self, max_message_len: usize) -> Self {
+        self.max_message_len = max_message_len;
+        self
+    }
+
+    /// Returns the underlying stream, e.g. to set timeouts.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Sends the input of a frame to the device.
+    pub fn send_input(&mut self, input: &egui::RawInput) -> io::Result<()> {
+        self.send(input)
+    }
+
+    /// Waits for the input of the next frame. Returns `None` if the viewer disconnected.
+    pub fn receive_input(&mut self) -> io::Result<Option<egui::RawInput>> {
+        self.receive()
+    }
+
+    /// Sends a frame to the viewer.
+    pub fn send_frame(&mut self, frame: &RemoteFrame) -> io::Result<()> {
+        self.send(frame)
+    }
+
+    /// Waits for the next frame. Returns `None` if the device disconnected.
+    pub fn receive_frame(&mut self) -> io::Result<Option<RemoteFrame>> {
+        self.receive()
+    }
+
+    fn send<M: Serialize>(&mut self, message: &M) -> io::Result<()> {
+        let stream = self.stream.get_mut();
+        serde_json::to_writer(&mut *stream, message)?;
+        stream.write_all(b"\n")?;
+        stream.flush()
+    }
+
+    fn receive<M: DeserializeOwned>(&mut self) -> io::Result<Option<M>> {
+        self.line.clear();
+        let limit = self.max_message_len as u64 + 1;
+        let len = (&mut self.stream).take(limit).read_line(&mut self.line)?;
+        if len == 0 {
+            return Ok(None);
+        }
+        if len > self.max_message_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "remote message exceeds the maximum length",
+            ));
+        }
+        serde_json::from_str(&self.line)
+            .map(Some)
+            .map_err(io::Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{TcpListener, TcpStream};
+
+    use super::*;
+    use crate::{Platform, PlatformDescriptor};
+
+    /// Serves a UI with a single button to two viewers, one after the other, and returns how
+    /// often the button was clicked.
+    fn serve(listener: TcpListener) -> io::Result<usize> {
+        let mut platform = Platform::new(PlatformDescriptor::new(800, 600, 1.0));
+        let mut clicks = 0;
+        for _ in 0..2 {
+            let mut connection = RemoteConnection::new(listener.accept()?.0);
+            platform.reset_remote_textures();
+            while let Some(input) = connection.receive_input()? {
+                platform.begin_remote_frame(input);
+                assert_eq!(platform.context().input(|i| i.max_texture_side), 1024);
+                egui::CentralPanel::default().show(&platform.context(), |ui| {
+                    let button = egui::Button::new("Click me");
+                    if ui.add_sized(ui.available_size(), button).clicked() {
+                        clicks += 1;
+                    }
+                });
+                connection.send_frame(&platform.end_remote_frame())?;
+            }
+        }
+        Ok(clicks)
+    }
+
+    #[test]
+    fn messages_longer_than_the_limit_are_rejected() {
+        let mut input = Vec::new();
+        RemoteConnection::new(io::Cursor::new(&mut input))
+            .send_input(&egui::RawInput::default())
+            .unwrap();
+        let len = input.len();
+
+        let mut connection =
+            RemoteConnection::new(io::Cursor::new(input.clone())).with_max_message_len(len);
+        assert!(connection.receive_input().unwrap().is_some());
+        assert!(connection.receive_input().unwrap().is_none());
+
+        let mut connection =
+            RemoteConnection::new(io::Cursor::new(input)).with_max_message_len(len - 1);
+        let err = connection.receive_input().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn has_font_texture(frame: &RemoteFrame) -> bool {
+        frame
+            .textures_delta
+            .set
+            .iter()
+            .any(|(id, delta)| *id == egui::TextureId::default() && delta.is_whole())
+    }
+
+    #[test]
+    fn click_round_trips_to_the_device() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let device = std::thread::spawn(move || serve(listener));
+
+        let button = egui::pos2(400.0, 300.0);
+        let click = |pressed| egui::Event::PointerButton {
+            pos: button,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        };
+
+        for _ in 0..2 {
+            let descriptor = PlatformDescriptor::new(800, 600, 1.0).with_max_texture_side(1024);
+            let mut viewer = Platform::new(descriptor);
+            let mut connection = RemoteConnection::new(TcpStream::connect(address).unwrap());
+            let mut frames = Vec::new();
+            for events in [
+                vec![egui::Event::PointerMoved(button)],
+                vec![click(true)],
+                vec![click(false)],
+            ] {
+                viewer.raw_input_mut().events.extend(events);
+                connection.send_input(&viewer.take_remote_input()).unwrap();
+                frames.push(connection.receive_frame().unwrap().unwrap());
+            }
+
+            // Every viewer receives the font atlas with its first frame.
+            assert!(has_font_texture(&frames[0]));
+            assert!(frames.iter().all(|frame| !frame.meshes.is_empty()));
+            // The pressed button is drawn differently.
+            let vertices = |frame: &RemoteFrame| frame.meshes[0].mesh.vertices.clone();
+            assert_ne!(vertices(&frames[0]), vertices(&frames[1]));
+        }
+
+        assert_eq!(device.join().unwrap().unwrap(), 2);
+    }
+}
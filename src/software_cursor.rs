@@ -0,0 +1,107 @@
+//! Cursors drawn by egui instead of the operating system, for custom cursor shapes.
+
+/// Identifies a cursor in the [`SoftwareCursor`] registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorId {
+    /// The cursor egui requests, e.g. `egui::CursorIcon::Text` over a text field.
+    Icon(egui::CursorIcon),
+    /// A cursor defined by the application, selected with [`SoftwareCursor::set_custom_cursor`].
+    Custom(u64),
+}
+
+/// The image of a software cursor. Positions and sizes are in points.
+#[derive(Debug, Clone)]
+pub enum CursorImage {
+    /// Shapes drawn relative to the pointer position, which is at the origin.
+    Shapes(Vec<egui::Shape>),
+    /// A texture, for example one allocated with `egui::Context::load_texture`.
+    Texture {
+        /// The texture to draw.
+        texture_id: egui::TextureId,
+        /// Size of the drawn texture.
+        size: egui::Vec2,
+        /// Offset from the top left corner of the texture to the point that is placed at the
+        /// pointer position.
+        hotspot: egui::Vec2,
+    },
+}
+
+impl CursorImage {
+    fn shapes(&self, pointer_pos: egui::Pos2) -> Vec<egui::Shape> {
+        match self {
+            CursorImage::Shapes(shapes) => {
+                let mut shapes = shapes.clone();
+                for shape in &mut shapes {
+                    shape.translate(pointer_pos.to_vec2());
+                }
+                shapes
+            }
+            CursorImage::Texture {
+                texture_id,
+                size,
+                hotspot,
+            } => {
+                let rect = egui::Rect::from_min_size(pointer_pos - *hotspot, *size);
+                let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                vec![egui::Shape::image(
+                    *texture_id,
+                    rect,
+                    uv,
+                    egui::Color32::WHITE,
+                )]
+            }
+        }
+    }
+}
+
+/// A registry of cursor images that are drawn by egui at the pointer position.
+///
+/// Set it with [`Platform::set_software_cursor`](crate::Platform::set_software_cursor). While a
+/// registered image is drawn the system cursor is hidden. Cursors without a registered image
+/// are shown by the system as usual.
+#[derive(Debug, Clone, Default)]
+pub struct SoftwareCursor {
+    images: Vec<(CursorId, CursorImage)>,
+    custom: Option<u64>,
+}
+
+impl SoftwareCursor {
+    /// Creates a new `SoftwareCursor` without any images.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the image of a cursor, replacing the previous one.
+    pub fn register(&mut self, id: CursorId, image: CursorImage) {
+        self.unregister(id);
+        self.images.push((id, image));
+    }
+
+    /// Removes the image of a cursor.
+    pub fn unregister(&mut self, id: CursorId) {
+        self.images.retain(|(registered, _)| *registered != id);
+    }
+
+    /// Shows the custom cursor with the given id instead of the cursor egui requests, for
+    /// example while the pointer is over a canvas. `None` shows the cursor egui requests.
+    pub fn set_custom_cursor(&mut self, id: Option<u64>) {
+        self.custom = id;
+    }
+
+    /// Returns the shapes of the current cursor at the pointer position, or `None` if no image
+    /// is registered for it.
+    pub(crate) fn shapes(
+        &self,
+        icon: egui::CursorIcon,
+        pointer_pos: egui::Pos2,
+    ) -> Option<Vec<egui::Shape>> {
+        let id = match self.custom {
+            Some(custom) => CursorId::Custom(custom),
+            None => CursorId::Icon(icon),
+        };
+        self.images
+            .iter()
+            .find(|(registered, _)| *registered == id)
+            .map(|(_, image)| image.shapes(pointer_pos))
+    }
+}